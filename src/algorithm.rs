@@ -0,0 +1,90 @@
+use crate::error::JwtError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 📌 Algoritmos de firma soportados
+///
+/// Se serializa con el nombre JOSE del algoritmo (`"HS256"`, `"RS256"`...),
+/// que es el mismo valor que aparece en la cabecera `alg` del token.
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default,
+)]
+pub enum Algorithm {
+  #[default]
+  HS256,
+  HS384,
+  HS512,
+  RS256,
+  RS384,
+  RS512,
+  PS256,
+  PS384,
+  PS512,
+  ES256,
+  ES384,
+  ES256K,
+  EdDSA,
+}
+impl Algorithm {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::HS256 => "HS256",
+      Self::HS384 => "HS384",
+      Self::HS512 => "HS512",
+      Self::RS256 => "RS256",
+      Self::RS384 => "RS384",
+      Self::RS512 => "RS512",
+      Self::PS256 => "PS256",
+      Self::PS384 => "PS384",
+      Self::PS512 => "PS512",
+      Self::ES256 => "ES256",
+      Self::ES384 => "ES384",
+      Self::ES256K => "ES256K",
+      Self::EdDSA => "EdDSA",
+    }
+  }
+
+  /// Indica si el algoritmo usa un secreto compartido (HMAC).
+  pub fn is_hmac(&self) -> bool {
+    matches!(self, Self::HS256 | Self::HS384 | Self::HS512)
+  }
+
+  /// Longitud en bytes del resumen (hash) que usa el algoritmo.
+  pub fn hash_len(&self) -> usize {
+    match self {
+      Self::HS256 | Self::RS256 | Self::PS256 | Self::ES256 | Self::ES256K => {
+        32
+      }
+      Self::HS384 | Self::RS384 | Self::PS384 | Self::ES384 => 48,
+      Self::HS512 | Self::RS512 | Self::PS512 | Self::EdDSA => 64,
+    }
+  }
+}
+impl fmt::Display for Algorithm {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+impl FromStr for Algorithm {
+  type Err = JwtError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "HS256" => Ok(Self::HS256),
+      "HS384" => Ok(Self::HS384),
+      "HS512" => Ok(Self::HS512),
+      "RS256" => Ok(Self::RS256),
+      "RS384" => Ok(Self::RS384),
+      "RS512" => Ok(Self::RS512),
+      "PS256" => Ok(Self::PS256),
+      "PS384" => Ok(Self::PS384),
+      "PS512" => Ok(Self::PS512),
+      "ES256" => Ok(Self::ES256),
+      "ES384" => Ok(Self::ES384),
+      "ES256K" => Ok(Self::ES256K),
+      "EdDSA" => Ok(Self::EdDSA),
+      _ => Err(JwtError::UnsupportedAlgorithm(s.to_string())),
+    }
+  }
+}
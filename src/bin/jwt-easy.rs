@@ -0,0 +1,435 @@
+use clap::{Args, Parser, Subcommand};
+use jwt_wasm::core::{
+  self, allowed_token_algorithm, private_signing_key, secret_signing_key,
+  secret_verifying_key,
+};
+use jwt_wasm::{
+  Algorithm, GeneratedKey, Jwk, JwkSet, JwtError, JwtOptions, KeyGenOptions,
+  KeyMaterial, Secret, UnverifiedToken, VerifyOptions, VerifyingKey,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Firma, verifica y decodifica JWT y genera claves sin salir de la
+/// terminal
+///
+/// Usa las mismas funciones que `create_jwt` y `verify_jwt`. Los resultados
+/// se escriben en stdout como JSON (salvo el token de `sign`) y los errores
+/// en stderr como `{ code, message, details }`, con código de salida 1.
+#[derive(Parser)]
+#[command(name = "jwt-easy", version)]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Firma un payload JSON y escribe el token
+  Sign(SignArgs),
+  /// Verifica un token y escribe `{ header, claims, payload }`
+  Verify(VerifyArgs),
+  /// Decodifica un token SIN verificar la firma
+  Decode(DecodeArgs),
+  /// Genera una clave nueva para el algoritmo indicado
+  Keygen(KeygenArgs),
+  /// Convierte claves PEM, DER o JWK en un JWKS con su parte pública
+  Jwks(JwksArgs),
+}
+
+#[derive(Args)]
+struct SignArgs {
+  /// Fichero con el payload JSON; sin él (o con `-`) se lee de stdin
+  payload: Option<PathBuf>,
+  /// Secreto HMAC
+  #[arg(long, env = "JWT_SECRET", hide_env_values = true)]
+  secret: Option<String>,
+  /// Fichero con el secreto HMAC
+  #[arg(long)]
+  secret_file: Option<PathBuf>,
+  /// Clave privada en PEM, DER o JWK; tiene prioridad sobre el secreto
+  #[arg(long)]
+  key: Option<PathBuf>,
+  /// Algoritmo de firma
+  #[arg(long, default_value = "HS256")]
+  alg: String,
+  /// Duración del token, como `15m`, `7d` o milisegundos
+  #[arg(long, visible_alias = "exp", default_value = "1h")]
+  expires_in: String,
+  /// Claim `iss`
+  #[arg(long)]
+  iss: Option<String>,
+  /// Claim `sub`
+  #[arg(long)]
+  sub: Option<String>,
+  /// Claim `aud`; se puede repetir
+  #[arg(long)]
+  aud: Vec<String>,
+  /// Claim `jti`
+  #[arg(long, conflicts_with = "generate_jti")]
+  jti: Option<String>,
+  /// Genera un `jti` aleatorio
+  #[arg(long)]
+  generate_jti: bool,
+  /// Retraso del claim `nbf` respecto a `iat`, como `30s`
+  #[arg(long)]
+  nbf: Option<String>,
+  /// `kid` de la cabecera
+  #[arg(long)]
+  kid: Option<String>,
+  /// `typ` de la cabecera, como `at+jwt`
+  #[arg(long)]
+  typ: Option<String>,
+  /// `cty` de la cabecera
+  #[arg(long)]
+  cty: Option<String>,
+  /// Otros parámetros de la cabecera como objeto JSON, por ejemplo
+  /// `{"jku":"https://example.com/jwks.json"}`
+  #[arg(long)]
+  header: Option<String>,
+  /// Parámetro de `--header` que el verificador debe entender (`crit`); se
+  /// puede repetir
+  #[arg(long)]
+  crit: Vec<String>,
+  /// Acepta secretos más cortos que el mínimo. Solo para pruebas
+  #[arg(long)]
+  allow_weak_secret: bool,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+  /// El token; sin él (o con `-`) se lee de stdin
+  token: Option<String>,
+  /// Secreto HMAC
+  #[arg(long, env = "JWT_SECRET", hide_env_values = true)]
+  secret: Option<String>,
+  /// Fichero con el secreto HMAC
+  #[arg(long)]
+  secret_file: Option<PathBuf>,
+  /// Clave pública en PEM, DER o JWK, o un JWKS (`{ "keys": [...] }`);
+  /// tiene prioridad sobre el secreto
+  #[arg(long)]
+  key: Option<PathBuf>,
+  /// Algoritmo permitido; se puede repetir
+  #[arg(long)]
+  alg: Vec<String>,
+  /// Emisor (`iss`) permitido; se puede repetir
+  #[arg(long)]
+  iss: Vec<String>,
+  /// Audiencia (`aud`) permitida; se puede repetir
+  #[arg(long)]
+  aud: Vec<String>,
+  /// `sub` obligatorio
+  #[arg(long)]
+  sub: Option<String>,
+  /// `jti` obligatorio
+  #[arg(long)]
+  jti: Option<String>,
+  /// Tolerancia de reloj, como `30s`; por defecto, 15 minutos
+  #[arg(long)]
+  leeway: Option<String>,
+  /// Antigüedad máxima del token, como `1h`
+  #[arg(long)]
+  max_age: Option<String>,
+  /// `typ` obligatorio en la cabecera, como `at+jwt`
+  #[arg(long)]
+  typ: Option<String>,
+  /// Parámetro crítico (`crit`) que se acepta sea cual sea su valor; se
+  /// puede repetir
+  #[arg(long)]
+  crit: Vec<String>,
+  /// Acepta secretos más cortos que el mínimo. Solo para pruebas
+  #[arg(long)]
+  allow_weak_secret: bool,
+}
+
+#[derive(Args)]
+struct DecodeArgs {
+  /// El token; sin él (o con `-`) se lee de stdin
+  token: Option<String>,
+}
+
+#[derive(Args)]
+struct KeygenArgs {
+  /// Algoritmo con el que se usará la clave
+  algorithm: String,
+  /// Identificador de la clave
+  #[arg(long)]
+  kid: Option<String>,
+  /// Bytes del secreto HMAC
+  #[arg(long)]
+  secret_length: Option<usize>,
+  /// Tamaño del módulo RSA: 2048, 3072 o 4096
+  #[arg(long)]
+  modulus_bits: Option<usize>,
+}
+
+#[derive(Args)]
+struct JwksArgs {
+  /// Ficheros de clave, pública o privada
+  #[arg(required = true)]
+  keys: Vec<PathBuf>,
+  /// Algoritmo al que queda vinculada cada clave, en el mismo orden que los
+  /// ficheros; uno solo vale para todas
+  #[arg(long, required = true)]
+  alg: Vec<String>,
+  /// `kid` de cada clave, en el mismo orden que los ficheros
+  #[arg(long)]
+  kid: Vec<String>,
+}
+
+fn main() -> ExitCode {
+  let result = match Cli::parse().command {
+    Command::Sign(args) => sign(args),
+    Command::Verify(args) => verify(args),
+    Command::Decode(args) => decode(args),
+    Command::Keygen(args) => keygen(args),
+    Command::Jwks(args) => jwks(args),
+  };
+  // Un `| head` que cierra la salida antes de tiempo no es un error
+  match result {
+    Ok(output) => {
+      let _ = writeln!(io::stdout(), "{output}");
+      ExitCode::SUCCESS
+    }
+    Err(err) => {
+      let mut error = json!({ "code": err.code(), "message": err.to_string() });
+      if let Some(details) = err.details() {
+        error["details"] = details;
+      }
+      let _ = writeln!(io::stderr(), "{error}");
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn sign(args: SignArgs) -> Result<String, JwtError> {
+  let payload: Value = serde_json::from_str(&read_input(args.payload)?)
+    .map_err(|err| JwtError::PayloadParse(err.to_string()))?;
+
+  // Las opciones pasan por la misma deserialización que en `create_jwt`
+  let mut options = Map::new();
+  options.insert("algorithm".to_string(), json!(args.alg));
+  options.insert("expires_in".to_string(), json!(args.expires_in));
+  options.insert("generate_jwt_id".to_string(), json!(args.generate_jti));
+  options
+    .insert("allow_weak_secret".to_string(), json!(args.allow_weak_secret));
+  insert(&mut options, "issuer", args.iss);
+  insert(&mut options, "subject", args.sub);
+  insert_all(&mut options, "audience", args.aud);
+  insert(&mut options, "jwt_id", args.jti);
+  insert(&mut options, "not_before", args.nbf);
+  insert(&mut options, "key_id", args.kid);
+  insert(&mut options, "typ", args.typ);
+  insert(&mut options, "cty", args.cty);
+  if let Some(header) = args.header {
+    let header: Value = serde_json::from_str(&header)
+      .map_err(|err| JwtError::InvalidOptions(err.to_string()))?;
+    options.insert("header".to_string(), header);
+  }
+  insert_all(&mut options, "crit", args.crit);
+
+  let mut options: JwtOptions = serde_json::from_value(Value::Object(options))
+    .map_err(|err| JwtError::InvalidOptions(err.to_string()))?;
+  // El secreto no pasa por JSON, para que no quede copiado en un `Value`
+  let material = match &args.key {
+    Some(path) => Some(read_key(path)?),
+    None => {
+      options.set_secret(read_secret(args.secret, args.secret_file)?);
+      None
+    }
+  };
+
+  let key = match &material {
+    Some(material) => private_signing_key(&options, material)?,
+    None => secret_signing_key(&options)?,
+  };
+  core::sign(&payload, &key, &options)
+}
+
+fn verify(args: VerifyArgs) -> Result<String, JwtError> {
+  let token = read_token(args.token)?;
+
+  let mut options = Map::new();
+  options
+    .insert("allow_weak_secret".to_string(), json!(args.allow_weak_secret));
+  insert_all(&mut options, "allowed_algorithms", args.alg);
+  insert_all(&mut options, "allowed_issuers", args.iss);
+  insert_all(&mut options, "allowed_audiences", args.aud);
+  insert(&mut options, "required_subject", args.sub);
+  insert(&mut options, "required_jwt_id", args.jti);
+  insert(&mut options, "leeway", args.leeway);
+  insert(&mut options, "max_age", args.max_age);
+  insert(&mut options, "required_type", args.typ);
+  let mut options: VerifyOptions =
+    serde_json::from_value(Value::Object(options))
+      .map_err(|err| JwtError::InvalidOptions(err.to_string()))?;
+  for name in &args.crit {
+    options.critical_headers.accept(name)?;
+  }
+
+  let key = match &args.key {
+    Some(path) => public_or_jwks_key(&token, path, &options)?,
+    None => secret_verifying_key(
+      allowed_token_algorithm(&token, &options)?,
+      read_secret(args.secret, args.secret_file)?,
+      &options,
+    )?,
+  };
+  to_json(&core::verify_full(&token, &key, &options)?)
+}
+
+fn decode(args: DecodeArgs) -> Result<String, JwtError> {
+  to_json(&UnverifiedToken::decode(&read_token(args.token)?)?)
+}
+
+fn keygen(args: KeygenArgs) -> Result<String, JwtError> {
+  let options = KeyGenOptions {
+    kid: args.kid,
+    secret_length: args.secret_length,
+    modulus_bits: args.modulus_bits,
+  };
+  to_json(&GeneratedKey::generate(args.algorithm.parse()?, &options)?)
+}
+
+fn jwks(args: JwksArgs) -> Result<String, JwtError> {
+  if args.kid.len() > args.keys.len() {
+    return Err(JwtError::InvalidOptions(
+      "There are more key IDs than keys".to_string(),
+    ));
+  }
+  if args.alg.len() != 1 && args.alg.len() != args.keys.len() {
+    return Err(JwtError::InvalidOptions(
+      "Give one algorithm for all the keys or one per key".to_string(),
+    ));
+  }
+  let algorithms = args
+    .alg
+    .iter()
+    .map(|alg| alg.parse())
+    .collect::<Result<Vec<Algorithm>, _>>()?;
+  let mut kids = args.kid.into_iter();
+  let keys = args
+    .keys
+    .iter()
+    .enumerate()
+    .map(|(index, path)| {
+      let algorithm = algorithms[index.min(algorithms.len() - 1)];
+      core::public_jwk(algorithm, &read_key(path)?, kids.next())
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  to_json(&JwkSet::new(keys))
+}
+
+fn insert<T: Serialize>(
+  options: &mut Map<String, Value>,
+  name: &str,
+  value: Option<T>,
+) {
+  if let Some(value) = value {
+    options.insert(name.to_string(), json!(value));
+  }
+}
+
+// Una clave pública o un JWKS del que se elige la clave del token
+fn public_or_jwks_key(
+  token: &str,
+  path: &Path,
+  options: &VerifyOptions,
+) -> Result<VerifyingKey, JwtError> {
+  let bytes = read_file(path)?;
+  let jwks = serde_json::from_slice::<Value>(&bytes)
+    .ok()
+    .filter(|json| json.get("keys").is_some());
+  if let Some(jwks) = jwks {
+    let jwks: JwkSet = serde_json::from_value(jwks)
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))?;
+    return jwks.verifying_key(token);
+  }
+  let algorithm = allowed_token_algorithm(token, options)?;
+  VerifyingKey::from_material(algorithm, &key_material(bytes)?)
+}
+
+// Las listas vacías se omiten, como si la opción no se hubiera indicado
+fn insert_all(
+  options: &mut Map<String, Value>,
+  name: &str,
+  values: Vec<String>,
+) {
+  if !values.is_empty() {
+    options.insert(name.to_string(), json!(values));
+  }
+}
+
+fn read_key(path: &Path) -> Result<KeyMaterial, JwtError> {
+  key_material(read_file(path)?)
+}
+
+// Una clave PEM, un JWK en JSON o los bytes DER de la clave
+fn key_material(bytes: Vec<u8>) -> Result<KeyMaterial, JwtError> {
+  let bytes = Secret::new(bytes);
+  let text = std::str::from_utf8(bytes.expose()).unwrap_or_default().trim();
+  if text.starts_with("-----BEGIN") {
+    return Ok(KeyMaterial::Pem(text.into()));
+  }
+  if text.starts_with('{') {
+    return Ok(KeyMaterial::Jwk(Box::new(Jwk::from_json(text)?)));
+  }
+  Ok(KeyMaterial::Der(bytes.expose().clone().into()))
+}
+
+fn read_secret(
+  secret: Option<String>,
+  secret_file: Option<PathBuf>,
+) -> Result<Secret, JwtError> {
+  match (secret, secret_file) {
+    (Some(secret), _) => Ok(secret.into()),
+    (None, Some(path)) => {
+      let secret = Secret::new(read_file(&path)?);
+      let secret = std::str::from_utf8(secret.expose())
+        .map_err(|err| JwtError::InvalidKey(err.to_string()))?;
+      Ok(secret.trim_end_matches(['\r', '\n']).into())
+    }
+    (None, None) => Err(JwtError::InvalidOptions(
+      "A secret (--secret, --secret-file or JWT_SECRET) or a key (--key) is required"
+        .to_string(),
+    )),
+  }
+}
+
+fn read_token(token: Option<String>) -> Result<String, JwtError> {
+  match token.filter(|token| token != "-") {
+    Some(token) => Ok(token),
+    None => Ok(read_input(None)?.trim().to_string()),
+  }
+}
+
+// Lee el fichero indicado o, sin él (o con `-`), toda la entrada estándar
+fn read_input(path: Option<PathBuf>) -> Result<String, JwtError> {
+  match path.filter(|path| path != Path::new("-")) {
+    Some(path) => String::from_utf8(read_file(&path)?)
+      .map_err(|err| JwtError::InvalidOptions(err.to_string())),
+    None => {
+      let mut input = String::new();
+      io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|err| JwtError::InvalidOptions(format!("stdin: {err}")))?;
+      Ok(input)
+    }
+  }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, JwtError> {
+  fs::read(path).map_err(|err| {
+    JwtError::InvalidOptions(format!("{}: {err}", path.display()))
+  })
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, JwtError> {
+  serde_json::to_string_pretty(value)
+    .map_err(|err| JwtError::Internal(err.to_string()))
+}
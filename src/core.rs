@@ -0,0 +1,410 @@
+use crate::{
+  duration, keygen, keys, Algorithm, Jwk, JwtError, KeyMaterial, Secret,
+  SigningKey, TokenHeader, VerifiedToken, VerifyOptions, VerifyingKey,
+};
+use jwt_simple::prelude::*;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+pub trait Constructible<T> {
+  fn new(params: T) -> Self;
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JwtOptions {
+  #[serde(default, skip_serializing)]
+  secret: Secret,
+  #[serde(deserialize_with = "duration::deserialize_millis")]
+  expires_in: u64,
+  #[serde(default)]
+  algorithm: Algorithm,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  issuer: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  subject: Option<String>,
+  #[serde(
+    default,
+    alias = "audiences",
+    skip_serializing_if = "Option::is_none"
+  )]
+  audience: Option<Audience>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  jwt_id: Option<String>,
+  #[serde(default)]
+  generate_jwt_id: bool,
+  #[serde(
+    default,
+    deserialize_with = "duration::deserialize_optional_millis",
+    skip_serializing_if = "Option::is_none"
+  )]
+  not_before: Option<u64>,
+  #[serde(default, alias = "kid", skip_serializing_if = "Option::is_none")]
+  key_id: Option<String>,
+  #[serde(default)]
+  allow_weak_secret: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  typ: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  cty: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  header: Option<Map<String, Value>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  crit: Option<Vec<String>>,
+}
+impl Default for JwtOptions {
+  fn default() -> Self {
+    Self {
+      secret: Secret::default(),
+      expires_in: 60 * 60 * 1000, // 1 hour
+      algorithm: Algorithm::default(),
+      issuer: None,
+      subject: None,
+      audience: None,
+      jwt_id: None,
+      generate_jwt_id: false,
+      not_before: None,
+      key_id: None,
+      allow_weak_secret: false,
+      typ: None,
+      cty: None,
+      header: None,
+      crit: None,
+    }
+  }
+}
+
+/// 📌 Una o varias audiencias (`aud`)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Audience {
+  One(String),
+  Many(Vec<String>),
+}
+impl Constructible<(String, u64)> for JwtOptions {
+  fn new(params: (String, u64)) -> Self {
+    Self { secret: params.0.into(), expires_in: params.1, ..Default::default() }
+  }
+}
+impl Constructible<(String, u64, Algorithm)> for JwtOptions {
+  fn new(params: (String, u64, Algorithm)) -> Self {
+    Self {
+      secret: params.0.into(),
+      expires_in: params.1,
+      algorithm: params.2,
+      ..Default::default()
+    }
+  }
+}
+impl JwtOptions {
+  /// Construye los claims del token con el payload personalizado.
+  ///
+  /// La expiración respeta la precisión en milisegundos de `expires_in`, y
+  /// los claims registrados (`iss`, `sub`, `aud`, `jti`, `nbf`) se añaden
+  /// solo si están presentes en las opciones.
+  pub fn claims(&self, payload: Value) -> Result<JWTClaims<Value>, JwtError> {
+    if self.expires_in == 0 {
+      return Err(JwtError::InvalidOptions(
+        "expires_in must be greater than zero".to_string(),
+      ));
+    }
+    offset_time(Clock::now_since_epoch(), self.expires_in, "expires_in")?;
+    let mut claims = Claims::with_custom_claims(
+      payload,
+      Duration::from_millis(self.expires_in),
+    );
+
+    if let Some(issuer) = &self.issuer {
+      claims = claims.with_issuer(issuer);
+    }
+    if let Some(subject) = &self.subject {
+      claims = claims.with_subject(subject);
+    }
+    match &self.audience {
+      Some(Audience::One(audience)) => claims = claims.with_audience(audience),
+      Some(Audience::Many(audiences)) => {
+        claims = claims.with_audiences(audiences.iter().collect())
+      }
+      None => {}
+    }
+    if let Some(jwt_id) = &self.jwt_id {
+      claims = claims.with_jwt_id(jwt_id);
+    } else if self.generate_jwt_id {
+      claims = claims.with_jwt_id(random_jwt_id()?);
+    }
+    if let (Some(not_before), Some(issued_at)) =
+      (self.not_before, claims.issued_at)
+    {
+      let not_before = offset_time(issued_at, not_before, "not_before")?;
+      claims = claims.invalid_before(not_before);
+    }
+    Ok(claims)
+  }
+
+  /// Construye la cabecera JOSE del token.
+  ///
+  /// Parte de los parámetros de `header` (como `jku`, `x5t#S256` o
+  /// cualquier parámetro privado) y les aplica `typ`, `cty` y `crit`. Si
+  /// nadie indica un `typ`, se usa `JWT`. `alg` no se puede fijar, porque lo
+  /// decide la clave. Los parámetros de `crit` deben estar en `header`.
+  pub fn token_header(&self) -> Result<TokenHeader, JwtError> {
+    let mut header = self.detached_header()?;
+    header.typ.get_or_insert_with(|| "JWT".to_string());
+    Ok(header)
+  }
+
+  /// Cabecera de un JWS con el payload separado: la de `token_header`, pero
+  /// sin `typ` por defecto, porque el payload no es un JWT.
+  pub fn detached_header(&self) -> Result<TokenHeader, JwtError> {
+    let mut header = self.header.clone().unwrap_or_default();
+    if header.contains_key("alg") {
+      return Err(JwtError::InvalidOptions(
+        "The header cannot set `alg`, it is set by the key".to_string(),
+      ));
+    }
+    if let Some(typ) = &self.typ {
+      header.insert("typ".to_string(), typ.clone().into());
+    }
+    if let Some(cty) = &self.cty {
+      header.insert("cty".to_string(), cty.clone().into());
+    }
+    if let Some(crit) = &self.crit {
+      header.insert("crit".to_string(), crit.clone().into());
+    }
+    // Marcador: el `alg` real lo pone la clave al firmar
+    header.insert("alg".to_string(), Value::String(String::new()));
+    serde_json::from_value(Value::Object(header))
+      .map_err(|err| JwtError::InvalidOptions(err.to_string()))
+  }
+}
+impl JwtOptions {
+  // static methods
+  pub fn new(secret: String, expires_in: u64) -> Self {
+    Self { secret: secret.into(), expires_in, ..Default::default() }
+  }
+  // instance methods
+  pub fn get_days(&self) -> u64 {
+    self.expires_in / 24 / 60 / 60 / 1000
+  }
+  pub fn get_hours(&self) -> u64 {
+    self.expires_in / 60 / 60 / 1000
+  }
+  pub fn get_minutes(&self) -> u64 {
+    self.expires_in / 60 / 1000
+  }
+  pub fn get_seconds(&self) -> u64 {
+    self.expires_in / 1000
+  }
+  pub fn get_milliseconds(&self) -> u64 {
+    self.expires_in
+  }
+  /// Secreto HMAC con el que se firma si no se indica otra clave.
+  pub fn set_secret(&mut self, secret: Secret) {
+    self.secret = secret;
+  }
+  pub fn set_issuer(&mut self, issuer: String) {
+    self.issuer = Some(issuer);
+  }
+  pub fn set_subject(&mut self, subject: String) {
+    self.subject = Some(subject);
+  }
+  pub fn set_audiences(&mut self, audiences: Vec<String>) {
+    self.audience = Some(Audience::Many(audiences));
+  }
+  pub fn set_jwt_id(&mut self, jwt_id: String) {
+    self.jwt_id = Some(jwt_id);
+  }
+  pub fn set_generate_jwt_id(&mut self, generate_jwt_id: bool) {
+    self.generate_jwt_id = generate_jwt_id;
+  }
+  /// Desactiva la longitud mínima del secreto HMAC. Solo para pruebas.
+  pub fn set_allow_weak_secret(&mut self, allow_weak_secret: bool) {
+    self.allow_weak_secret = allow_weak_secret;
+  }
+  /// Identificador de la clave (`kid`) que se incluye en la cabecera.
+  pub fn set_key_id(&mut self, key_id: String) {
+    self.key_id = Some(key_id);
+  }
+  /// Tipo del token (`typ`), como `at+jwt` para un access token.
+  pub fn set_typ(&mut self, typ: String) {
+    self.typ = Some(typ);
+  }
+  /// Tipo del contenido (`cty`) que se incluye en la cabecera.
+  pub fn set_cty(&mut self, cty: String) {
+    self.cty = Some(cty);
+  }
+  /// Parámetros de `header` que el verificador debe entender (`crit`).
+  pub fn set_crit(&mut self, crit: Vec<String>) {
+    self.crit = Some(crit);
+  }
+  /// Acepta una duración legible como `"15m"`, `"7d"` o `"90s"`.
+  pub fn set_expires_in(&mut self, expires_in: &str) -> Result<(), JwtError> {
+    let millis =
+      duration::parse_millis(expires_in).map_err(JwtError::InvalidOptions)?;
+    if millis == 0 {
+      return Err(JwtError::InvalidOptions(
+        "expires_in must be greater than zero".to_string(),
+      ));
+    }
+    self.expires_in = millis;
+    Ok(())
+  }
+  pub fn get_algorithm(&self) -> String {
+    self.algorithm.to_string()
+  }
+  pub fn set_algorithm(&mut self, algorithm: &str) -> Result<(), JwtError> {
+    self.algorithm = algorithm.parse()?;
+    Ok(())
+  }
+}
+
+/// 📌 Firma un payload con la clave indicada
+///
+/// Los claims registrados (expiración, `iss`, `sub`, `aud`...) y la cabecera
+/// (`typ`, `cty`, `header`) se toman de las opciones; su `secret` y su
+/// `algorithm` no se usan, porque la clave ya tiene el suyo.
+pub fn sign<C: Serialize>(
+  payload: &C,
+  key: &SigningKey,
+  options: &JwtOptions,
+) -> Result<String, JwtError> {
+  let payload = serde_json::to_value(payload)
+    .map_err(|err| JwtError::PayloadParse(err.to_string()))?;
+  key.sign_with_header(options.claims(payload)?, options.token_header()?)
+}
+
+/// 📌 Firma un payload arbitrario como JWS separado (RFC 7797)
+///
+/// El payload se firma sin codificar (`b64: false`) y no se incluye en el
+/// token, que queda como `cabecera..firma`. La cabecera sale de las opciones
+/// (`typ`, `cty`, `header`, `crit`); los claims no se usan.
+pub fn sign_detached(
+  payload: &[u8],
+  key: &SigningKey,
+  options: &JwtOptions,
+) -> Result<String, JwtError> {
+  key.sign_detached(payload, options.detached_header()?)
+}
+
+/// 📌 Verifica un JWS separado con su payload y devuelve la cabecera
+pub fn verify_detached(
+  token: &str,
+  payload: &[u8],
+  key: &VerifyingKey,
+  options: &VerifyOptions,
+) -> Result<TokenHeader, JwtError> {
+  key.verify_detached(token, payload, options)
+}
+
+/// 📌 Verifica el token y devuelve su payload con el tipo indicado
+///
+/// Comprueba la firma y los claims registrados igual que `verify_jwt`. Si el
+/// payload no se puede deserializar en `C`, el error es `PayloadParse`.
+pub fn verify<C: DeserializeOwned>(
+  token: &str,
+  key: &VerifyingKey,
+  options: &VerifyOptions,
+) -> Result<C, JwtError> {
+  let claims = key.verify_with(token, options)?;
+  serde_json::from_value(claims.custom)
+    .map_err(|err| JwtError::PayloadParse(err.to_string()))
+}
+
+/// 📌 Verifica el token y devuelve la cabecera, los claims registrados y el
+/// payload
+pub fn verify_full(
+  token: &str,
+  key: &VerifyingKey,
+  options: &VerifyOptions,
+) -> Result<VerifiedToken, JwtError> {
+  key.verify_full(token, options)
+}
+
+/// Clave HMAC con el `secret`, el algoritmo y el `key_id` de las opciones.
+pub fn secret_signing_key(
+  options: &JwtOptions,
+) -> Result<SigningKey, JwtError> {
+  let secret = options.secret.expose().as_bytes();
+  let key = if options.allow_weak_secret {
+    SigningKey::from_weak_secret(options.algorithm, secret)?
+  } else {
+    SigningKey::from_secret(options.algorithm, secret)?
+  };
+  Ok(with_options_key_id(key, options))
+}
+
+/// Clave privada con el algoritmo y el `key_id` de las opciones.
+pub fn private_signing_key(
+  options: &JwtOptions,
+  material: &KeyMaterial,
+) -> Result<SigningKey, JwtError> {
+  let key = SigningKey::from_material(options.algorithm, material)?;
+  Ok(with_options_key_id(key, options))
+}
+
+/// Clave HMAC de verificación; respeta `allow_weak_secret`.
+pub fn secret_verifying_key(
+  algorithm: Algorithm,
+  secret: Secret,
+  options: &VerifyOptions,
+) -> Result<VerifyingKey, JwtError> {
+  let secret = secret.expose();
+  if options.allow_weak_secret {
+    VerifyingKey::from_weak_secret(algorithm, secret.as_bytes())
+  } else {
+    VerifyingKey::from_secret(algorithm, secret.as_bytes())
+  }
+}
+
+/// Algoritmo de la cabecera del token, si está entre los permitidos.
+///
+/// El `alg` del token solo sirve para elegir la clave; la clave importada
+/// queda ligada a ese algoritmo.
+pub fn allowed_token_algorithm(
+  token: &str,
+  options: &VerifyOptions,
+) -> Result<Algorithm, JwtError> {
+  let algorithm = keys::token_algorithm(token)?;
+  options.check_algorithm(algorithm)?;
+  Ok(algorithm)
+}
+
+/// Parte pública de una clave, pública o privada, como JWK.
+pub fn public_jwk(
+  algorithm: Algorithm,
+  material: &KeyMaterial,
+  kid: Option<String>,
+) -> Result<Jwk, JwtError> {
+  let jwk = match VerifyingKey::from_material(algorithm, material) {
+    Ok(key) => key.to_jwk()?,
+    Err(err) => SigningKey::from_material(algorithm, material)
+      .map_err(|_| err)?
+      .to_jwk()?,
+  };
+  Ok(jwk.to_public()?.with_key_id(kid))
+}
+
+// Suma una duración en milisegundos a un instante. `jwt-simple` guarda los
+// segundos en 32 bits y no comprueba el desbordamiento, así que lo que no
+// cabe es un error de las opciones
+fn offset_time(
+  time: UnixTimeStamp,
+  millis: u64,
+  name: &str,
+) -> Result<UnixTimeStamp, JwtError> {
+  (millis / 1000 <= u32::MAX as u64)
+    .then(|| time.checked_add(Duration::from_millis(millis)))
+    .flatten()
+    .ok_or_else(|| JwtError::InvalidOptions(format!("{name} is too large")))
+}
+
+// Identificador aleatorio de 128 bits codificado en base64url
+fn random_jwt_id() -> Result<String, JwtError> {
+  keygen::random_base64url(16)
+}
+
+// Los tokens llevan en la cabecera el `kid` de las opciones, si lo hay
+fn with_options_key_id(key: SigningKey, options: &JwtOptions) -> SigningKey {
+  match &options.key_id {
+    Some(key_id) => key.with_key_id(key_id),
+    None => key,
+  }
+}
@@ -0,0 +1,125 @@
+use crate::error::JwtError;
+use crate::token::TokenHeader;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Comprueba el valor de un parámetro crítico; recibe el valor y la cabecera
+/// completa del token.
+pub type CriticalHandler =
+  dyn Fn(&Value, &TokenHeader) -> Result<(), JwtError> + Send + Sync;
+
+// Parámetros de JWS, JWE y JWA, que nunca pueden aparecer en `crit`
+// (RFC 7515, 4.1.11)
+const REGISTERED_PARAMETERS: [&str; 20] = [
+  "alg", "enc", "zip", "jku", "jwk", "kid", "x5u", "x5c", "x5t", "x5t#S256",
+  "typ", "cty", "crit", "epk", "apu", "apv", "iv", "tag", "p2s", "p2c",
+];
+
+/// 📌 Registro de extensiones críticas de la cabecera (`crit`)
+///
+/// Un token que declara en `crit` un parámetro sin manejador registrado se
+/// rechaza con `UnsupportedCritical`, de modo que un servicio que no conoce
+/// una extensión falla cerrado en lugar de ignorarla. El manejador recibe el
+/// valor del parámetro y decide si el token es aceptable; para rechazarlo
+/// devuelve un error, normalmente `CriticalRejected`.
+#[derive(Clone, Default)]
+pub struct CriticalHeaders {
+  handlers: HashMap<String, Arc<CriticalHandler>>,
+}
+impl CriticalHeaders {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Registra el manejador de un parámetro crítico. Los parámetros
+  /// registrados de JOSE (`alg`, `kid`...) no pueden ser críticos.
+  pub fn register<F>(&mut self, name: &str, handler: F) -> Result<(), JwtError>
+  where
+    F: Fn(&Value, &TokenHeader) -> Result<(), JwtError> + Send + Sync + 'static,
+  {
+    if REGISTERED_PARAMETERS.contains(&name) {
+      return Err(JwtError::InvalidOptions(format!(
+        "`{name}` cannot be a critical header parameter"
+      )));
+    }
+    self.handlers.insert(name.to_string(), Arc::new(handler));
+    Ok(())
+  }
+
+  /// Acepta el parámetro crítico sea cual sea su valor.
+  pub fn accept(&mut self, name: &str) -> Result<(), JwtError> {
+    self.register(name, |_, _| Ok(()))
+  }
+
+  pub fn remove(&mut self, name: &str) -> bool {
+    self.handlers.remove(name).is_some()
+  }
+
+  pub fn contains(&self, name: &str) -> bool {
+    self.handlers.contains_key(name)
+  }
+
+  /// Comprueba los parámetros críticos de la cabecera de un token recibido.
+  pub fn check(&self, header: &TokenHeader) -> Result<(), JwtError> {
+    self.check_understood(header, &[])
+  }
+
+  // Igual que `check`, pero los parámetros de `understood` los entiende ya
+  // quien verifica, como `b64` en los JWS separados
+  pub(crate) fn check_understood(
+    &self,
+    header: &TokenHeader,
+    understood: &[&str],
+  ) -> Result<(), JwtError> {
+    check_names(header).map_err(JwtError::Malformed)?;
+    for name in header.crit.iter().flatten() {
+      if understood.contains(&name.as_str()) {
+        continue;
+      }
+      let handler = self.handlers.get(name).ok_or_else(|| {
+        JwtError::UnsupportedCritical { parameter: name.clone() }
+      })?;
+      handler(&header.extra[name], header)?;
+    }
+    Ok(())
+  }
+}
+impl fmt::Debug for CriticalHeaders {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.handlers.keys()).finish()
+  }
+}
+
+/// Comprueba que `crit` está bien formado: no está vacío, no repite nombres,
+/// no incluye parámetros registrados y todos están en la cabecera.
+pub(crate) fn check_names(header: &TokenHeader) -> Result<(), String> {
+  check_list(header.crit.as_deref(), &header.extra)
+}
+
+// Igual que `check_names` para cualquier cabecera JOSE, también la de un JWE;
+// `extra` son los parámetros no registrados de la cabecera
+pub(crate) fn check_list(
+  crit: Option<&[String]>,
+  extra: &Map<String, Value>,
+) -> Result<(), String> {
+  let Some(crit) = crit else {
+    return Ok(());
+  };
+  if crit.is_empty() {
+    return Err("The `crit` header parameter cannot be empty".to_string());
+  }
+  for (index, name) in crit.iter().enumerate() {
+    if REGISTERED_PARAMETERS.contains(&name.as_str()) {
+      return Err(format!("`{name}` cannot be a critical header parameter"));
+    }
+    if crit[..index].contains(name) {
+      return Err(format!("Duplicate critical header parameter: {name}"));
+    }
+    if !extra.contains_key(name) {
+      return Err(format!("Critical header parameter is missing: {name}"));
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,122 @@
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use std::fmt;
+
+const SECOND: f64 = 1000.0;
+const MINUTE: f64 = SECOND * 60.0;
+const HOUR: f64 = MINUTE * 60.0;
+const DAY: f64 = HOUR * 24.0;
+const WEEK: f64 = DAY * 7.0;
+const YEAR: f64 = DAY * 365.25;
+
+/// 📌 Convierte una duración legible en milisegundos
+///
+/// Sigue el formato de `expiresIn` de `jsonwebtoken`: `"90s"`, `"15m"`,
+/// `"2 hours"`, `"7d"`... Una cadena sin unidad se interpreta en
+/// milisegundos (`"120"` equivale a `"120ms"`).
+pub fn parse_millis(value: &str) -> Result<u64, String> {
+  let value = value.trim();
+  let split = value
+    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+    .unwrap_or(value.len());
+  let (amount, unit) = value.split_at(split);
+  let amount: f64 =
+    amount.parse().map_err(|_| format!("Invalid duration: \"{value}\""))?;
+  let factor = match unit.trim().to_lowercase().as_str() {
+    "" | "ms" | "msec" | "msecs" | "millisecond" | "milliseconds" => 1.0,
+    "s" | "sec" | "secs" | "second" | "seconds" => SECOND,
+    "m" | "min" | "mins" | "minute" | "minutes" => MINUTE,
+    "h" | "hr" | "hrs" | "hour" | "hours" => HOUR,
+    "d" | "day" | "days" => DAY,
+    "w" | "week" | "weeks" => WEEK,
+    "y" | "yr" | "yrs" | "year" | "years" => YEAR,
+    unit => return Err(format!("Unknown duration unit: \"{unit}\"")),
+  };
+  float_millis(amount * factor).map_err(|err| format!("{err}: \"{value}\""))
+}
+
+/// 📌 Deserializa una duración en milisegundos
+///
+/// Acepta un número de milisegundos, una cadena legible (ver
+/// [`parse_millis`]) o un objeto `{ seconds }` / `{ milliseconds }`.
+/// Una duración de cero se rechaza de forma explícita.
+pub fn deserialize_millis<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let millis = deserializer.deserialize_any(MillisVisitor)?;
+  if millis == 0 {
+    return Err(de::Error::custom("duration must be greater than zero"));
+  }
+  Ok(millis)
+}
+
+/// 📌 Deserializa un desplazamiento opcional en milisegundos
+///
+/// Igual que [`deserialize_millis`], pero admite cero (por ejemplo, un
+/// `not_before` que empieza a contar desde el momento de la firma).
+pub fn deserialize_optional_millis<'de, D>(
+  deserializer: D,
+) -> Result<Option<u64>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  deserializer.deserialize_any(MillisVisitor).map(Some)
+}
+
+struct MillisVisitor;
+
+impl<'de> Visitor<'de> for MillisVisitor {
+  type Value = u64;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("milliseconds, a duration string or { seconds }")
+  }
+
+  fn visit_u64<E: de::Error>(self, value: u64) -> Result<u64, E> {
+    Ok(value)
+  }
+
+  fn visit_i64<E: de::Error>(self, value: i64) -> Result<u64, E> {
+    u64::try_from(value).map_err(|_| E::custom("duration cannot be negative"))
+  }
+
+  fn visit_f64<E: de::Error>(self, value: f64) -> Result<u64, E> {
+    float_millis(value).map_err(E::custom)
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+    parse_millis(value).map_err(E::custom)
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<u64, A::Error> {
+    let mut millis = None;
+    while let Some(key) = map.next_key::<String>()? {
+      let value: f64 = map.next_value()?;
+      let factor = match key.as_str() {
+        "seconds" => SECOND,
+        "milliseconds" => 1.0,
+        _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+      };
+      if millis.is_some() {
+        return Err(de::Error::custom("duration has more than one unit"));
+      }
+      millis = Some(float_millis(value * factor).map_err(de::Error::custom)?);
+    }
+    millis.ok_or_else(|| de::Error::missing_field("seconds"))
+  }
+}
+
+const FIELDS: &[&str] = &["seconds", "milliseconds"];
+
+// Redondea a milisegundos enteros; lo que no cabe en un `u64` es un error,
+// no un valor saturado
+pub(crate) fn float_millis(value: f64) -> Result<u64, &'static str> {
+  if value.is_nan() || value < 0.0 {
+    return Err("duration cannot be negative");
+  }
+  let millis = value.round();
+  if millis >= u64::MAX as f64 {
+    return Err("duration is too large");
+  }
+  Ok(millis as u64)
+}
@@ -0,0 +1,99 @@
+use serde_json::{json, Value};
+
+/// 📌 Errores de la librería con un código estable
+///
+/// El código (`code`) es el nombre de la variante y no cambia entre
+/// versiones, a diferencia del mensaje, que es solo informativo.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JwtError {
+  #[error("Token has expired")]
+  Expired { expired_at: Option<u64> },
+  #[error("Token is not valid yet")]
+  NotYetValid { not_before: Option<u64> },
+  #[error("Token is too old")]
+  TooOld,
+  #[error("Invalid token signature")]
+  InvalidSignature,
+  #[error("Token could not be decrypted")]
+  DecryptionFailed,
+  #[error("Malformed token: {0}")]
+  Malformed(String),
+  #[error("Token algorithm does not match the key")]
+  AlgorithmMismatch,
+  #[error("Unsupported algorithm: {0}")]
+  UnsupportedAlgorithm(String),
+  #[error("Token key ID does not match")]
+  KeyIdMismatch,
+  #[error("Token key has been retired")]
+  KeyRetired { retired_at: Option<u64> },
+  #[error("Token audience is not allowed")]
+  AudienceMismatch,
+  #[error("Token issuer is not allowed")]
+  IssuerMismatch,
+  #[error("Token subject does not match")]
+  SubjectMismatch,
+  #[error("Token JWT ID does not match")]
+  JwtIdMismatch,
+  #[error("Token type does not match")]
+  TypeMismatch,
+  #[error("Unsupported critical header parameter: {parameter}")]
+  UnsupportedCritical { parameter: String },
+  #[error("Critical header parameter was rejected: {parameter}")]
+  CriticalRejected { parameter: String },
+  #[error("Invalid key: {0}")]
+  InvalidKey(String),
+  #[error("Invalid options: {0}")]
+  InvalidOptions(String),
+  #[error("Failed to parse payload: {0}")]
+  PayloadParse(String),
+  #[error("Internal error: {0}")]
+  Internal(String),
+}
+impl JwtError {
+  /// Código estable del error.
+  pub fn code(&self) -> &'static str {
+    match self {
+      Self::Expired { .. } => "Expired",
+      Self::NotYetValid { .. } => "NotYetValid",
+      Self::TooOld => "TooOld",
+      Self::InvalidSignature => "InvalidSignature",
+      Self::DecryptionFailed => "DecryptionFailed",
+      Self::Malformed(_) => "Malformed",
+      Self::AlgorithmMismatch => "AlgorithmMismatch",
+      Self::UnsupportedAlgorithm(_) => "UnsupportedAlgorithm",
+      Self::KeyIdMismatch => "KeyIdMismatch",
+      Self::KeyRetired { .. } => "KeyRetired",
+      Self::AudienceMismatch => "AudienceMismatch",
+      Self::IssuerMismatch => "IssuerMismatch",
+      Self::SubjectMismatch => "SubjectMismatch",
+      Self::JwtIdMismatch => "JwtIdMismatch",
+      Self::TypeMismatch => "TypeMismatch",
+      Self::UnsupportedCritical { .. } => "UnsupportedCritical",
+      Self::CriticalRejected { .. } => "CriticalRejected",
+      Self::InvalidKey(_) => "InvalidKey",
+      Self::InvalidOptions(_) => "InvalidOptions",
+      Self::PayloadParse(_) => "PayloadParse",
+      Self::Internal(_) => "Internal",
+    }
+  }
+
+  /// Información adicional del error, como la fecha de expiración.
+  pub fn details(&self) -> Option<Value> {
+    match self {
+      Self::Expired { expired_at: Some(expired_at) } => {
+        Some(json!({ "expired_at": expired_at }))
+      }
+      Self::NotYetValid { not_before: Some(not_before) } => {
+        Some(json!({ "not_before": not_before }))
+      }
+      Self::KeyRetired { retired_at: Some(retired_at) } => {
+        Some(json!({ "retired_at": retired_at }))
+      }
+      Self::UnsupportedCritical { parameter }
+      | Self::CriticalRejected { parameter } => {
+        Some(json!({ "parameter": parameter }))
+      }
+      _ => None,
+    }
+  }
+}
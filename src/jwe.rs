@@ -0,0 +1,989 @@
+use crate::algorithm::Algorithm;
+use crate::critical;
+use crate::error::JwtError;
+use crate::jwk::Jwk;
+use crate::keys::{KeyMaterial, SigningKey, VerifyingKey};
+use crate::secret::Secret;
+use crate::token::{TokenHeader, VerifiedToken, MAX_HEADER_LENGTH};
+use crate::validation::VerifyOptions;
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes128Gcm, Aes256Gcm, Nonce, Tag};
+use aes_kw::{KekAes128, KekAes256};
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use hmac::{Hmac, Mac};
+use jwt_simple::prelude::*;
+use jwt_simple::{Error, JWTError};
+use p256::ecdh::EphemeralSecret;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::pkcs8::{DecodePrivateKey as _, DecodePublicKey as _};
+use rand_core::{OsRng, RngCore};
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+use rsa::pkcs8::{DecodePrivateKey as _, DecodePublicKey as _};
+use rsa::{
+  BigUint, PaddingScheme, PublicKey, PublicKeyParts, RsaPrivateKey,
+  RsaPublicKey,
+};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+const MIN_RSA_MODULUS_BITS: usize = 2048;
+
+/// 📌 Algoritmos de gestión de la clave de contenido de un JWE (`alg`)
+///
+/// - `dir` - La clave compartida cifra el contenido directamente.
+/// - `A128KW`, `A256KW` - Una clave compartida de 16 o 32 bytes envuelve una
+///   clave de contenido aleatoria (AES Key Wrap).
+/// - `RSA-OAEP-256` - La clave pública RSA del destinatario cifra la clave de
+///   contenido.
+/// - `ECDH-ES`, `ECDH-ES+A256KW` - Se acuerda una clave con la clave pública
+///   P-256 del destinatario y una clave efímera (`epk`), y se usa
+///   directamente o para envolver la clave de contenido.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JweAlgorithm {
+  #[serde(rename = "dir")]
+  Dir,
+  A128KW,
+  A256KW,
+  #[serde(rename = "RSA-OAEP-256")]
+  RsaOaep256,
+  #[serde(rename = "ECDH-ES")]
+  EcdhEs,
+  #[serde(rename = "ECDH-ES+A256KW")]
+  EcdhEsA256KW,
+}
+impl JweAlgorithm {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Dir => "dir",
+      Self::A128KW => "A128KW",
+      Self::A256KW => "A256KW",
+      Self::RsaOaep256 => "RSA-OAEP-256",
+      Self::EcdhEs => "ECDH-ES",
+      Self::EcdhEsA256KW => "ECDH-ES+A256KW",
+    }
+  }
+}
+impl fmt::Display for JweAlgorithm {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+impl FromStr for JweAlgorithm {
+  type Err = JwtError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "dir" => Ok(Self::Dir),
+      "A128KW" => Ok(Self::A128KW),
+      "A256KW" => Ok(Self::A256KW),
+      "RSA-OAEP-256" => Ok(Self::RsaOaep256),
+      "ECDH-ES" => Ok(Self::EcdhEs),
+      "ECDH-ES+A256KW" => Ok(Self::EcdhEsA256KW),
+      _ => Err(JwtError::UnsupportedAlgorithm(s.to_string())),
+    }
+  }
+}
+
+/// 📌 Algoritmos de cifrado del contenido de un JWE (`enc`)
+///
+/// `A128GCM` y `A256GCM` usan AES-GCM; `A128CBC-HS256`, AES-CBC autenticado
+/// con HMAC-SHA256 (RFC 7518, 5.2).
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default,
+)]
+pub enum ContentEncryption {
+  A128GCM,
+  #[default]
+  A256GCM,
+  #[serde(rename = "A128CBC-HS256")]
+  A128CbcHs256,
+}
+impl ContentEncryption {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::A128GCM => "A128GCM",
+      Self::A256GCM => "A256GCM",
+      Self::A128CbcHs256 => "A128CBC-HS256",
+    }
+  }
+
+  /// Longitud en bytes de la clave de contenido.
+  pub fn key_len(&self) -> usize {
+    match self {
+      Self::A128GCM => 16,
+      Self::A256GCM | Self::A128CbcHs256 => 32,
+    }
+  }
+
+  fn iv_len(&self) -> usize {
+    match self {
+      Self::A128GCM | Self::A256GCM => 12,
+      Self::A128CbcHs256 => 16,
+    }
+  }
+
+  // Cifra el contenido y devuelve el texto cifrado y la etiqueta
+  fn seal(
+    &self,
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+  ) -> Result<(Vec<u8>, Vec<u8>), JwtError> {
+    let internal = |err: &dyn fmt::Display| JwtError::Internal(err.to_string());
+    match self {
+      Self::A128GCM | Self::A256GCM => {
+        let mut buffer = plaintext.to_vec();
+        let nonce = <[u8; 12]>::try_from(iv).map_err(|err| internal(&err))?;
+        let nonce = &Nonce::from(nonce);
+        let tag = if *self == Self::A128GCM {
+          Aes128Gcm::new_from_slice(cek)
+            .map_err(|err| internal(&err))?
+            .encrypt_in_place_detached(nonce, aad, &mut buffer)
+        } else {
+          Aes256Gcm::new_from_slice(cek)
+            .map_err(|err| internal(&err))?
+            .encrypt_in_place_detached(nonce, aad, &mut buffer)
+        };
+        let tag = tag.map_err(|err| internal(&err))?;
+        Ok((buffer, tag.to_vec()))
+      }
+      Self::A128CbcHs256 => {
+        let (mac_key, enc_key) = cek.split_at(cek.len() / 2);
+        let ciphertext =
+          cbc::Encryptor::<aes::Aes128>::new_from_slices(enc_key, iv)
+            .map_err(|err| internal(&err))?
+            .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+        let tag = cbc_hmac(mac_key, aad, iv, &ciphertext)
+          .map_err(|err| internal(&err))?
+          .finalize()
+          .into_bytes();
+        Ok((ciphertext, tag[..16].to_vec()))
+      }
+    }
+  }
+
+  // Comprueba la etiqueta y descifra el contenido
+  fn open(
+    &self,
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+  ) -> Result<Vec<u8>, JwtError> {
+    if iv.len() != self.iv_len() || tag.len() != 16 {
+      return Err(JwtError::DecryptionFailed);
+    }
+    match self {
+      Self::A128GCM | Self::A256GCM => {
+        let mut buffer = ciphertext.to_vec();
+        let nonce = <[u8; 12]>::try_from(iv)
+          .map(Nonce::from)
+          .map_err(|_| JwtError::DecryptionFailed)?;
+        let tag = <[u8; 16]>::try_from(tag)
+          .map(Tag::from)
+          .map_err(|_| JwtError::DecryptionFailed)?;
+        let opened = if *self == Self::A128GCM {
+          Aes128Gcm::new_from_slice(cek)
+            .map_err(|_| JwtError::DecryptionFailed)?
+            .decrypt_in_place_detached(&nonce, aad, &mut buffer, &tag)
+        } else {
+          Aes256Gcm::new_from_slice(cek)
+            .map_err(|_| JwtError::DecryptionFailed)?
+            .decrypt_in_place_detached(&nonce, aad, &mut buffer, &tag)
+        };
+        opened.map_err(|_| JwtError::DecryptionFailed)?;
+        Ok(buffer)
+      }
+      Self::A128CbcHs256 => {
+        let (mac_key, enc_key) = cek.split_at(cek.len() / 2);
+        cbc_hmac(mac_key, aad, iv, ciphertext)
+          .map_err(|_| JwtError::DecryptionFailed)?
+          .verify_truncated_left(tag)
+          .map_err(|_| JwtError::DecryptionFailed)?;
+        cbc::Decryptor::<aes::Aes128>::new_from_slices(enc_key, iv)
+          .map_err(|_| JwtError::DecryptionFailed)?
+          .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+          .map_err(|_| JwtError::DecryptionFailed)
+      }
+    }
+  }
+}
+impl fmt::Display for ContentEncryption {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+impl FromStr for ContentEncryption {
+  type Err = JwtError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "A128GCM" => Ok(Self::A128GCM),
+      "A256GCM" => Ok(Self::A256GCM),
+      "A128CBC-HS256" => Ok(Self::A128CbcHs256),
+      _ => Err(JwtError::UnsupportedAlgorithm(s.to_string())),
+    }
+  }
+}
+
+/// 📌 Cabecera protegida de un JWE
+///
+/// `epk`, `apu` y `apv` solo aparecen con `ECDH-ES`. Como en `TokenHeader`,
+/// los demás parámetros se conservan en `extra`; el descifrado no entiende
+/// ninguna extensión crítica, así que un JWE con `crit` se rechaza.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JweHeader {
+  pub alg: JweAlgorithm,
+  pub enc: ContentEncryption,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub typ: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub cty: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub kid: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub epk: Option<Jwk>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub apu: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub apv: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub crit: Option<Vec<String>>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+impl JweHeader {
+  pub fn new(alg: JweAlgorithm, enc: ContentEncryption) -> Self {
+    Self {
+      alg,
+      enc,
+      typ: None,
+      cty: None,
+      kid: None,
+      epk: None,
+      apu: None,
+      apv: None,
+      crit: None,
+      extra: Map::new(),
+    }
+  }
+
+  /// Lee la cabecera de un JWE sin descifrarlo.
+  pub fn decode(token: &str) -> Result<Self, JwtError> {
+    let header_b64 = token.split('.').next().unwrap_or_default();
+    if header_b64.len() > MAX_HEADER_LENGTH {
+      return Err(JwtError::Malformed("Token header is too large".to_string()));
+    }
+    let header = decode_part(header_b64)?;
+    serde_json::from_slice(&header)
+      .map_err(|err| JwtError::Malformed(err.to_string()))
+  }
+}
+
+/// 📌 Opciones de cifrado de un JWE
+///
+/// - `alg` - Gestión de la clave de contenido: `"dir"`, `"A128KW"`,
+///   `"A256KW"`, `"RSA-OAEP-256"`, `"ECDH-ES"` o `"ECDH-ES+A256KW"`.
+/// - `enc` - Cifrado del contenido: `"A128GCM"`, `"A256GCM"` (por defecto) o
+///   `"A128CBC-HS256"`.
+/// - `key_id` (o `kid`) - Identificador de la clave del destinatario, que se
+///   añade a la cabecera.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JweOptions {
+  pub alg: JweAlgorithm,
+  #[serde(default)]
+  pub enc: ContentEncryption,
+  #[serde(default, alias = "kid", skip_serializing_if = "Option::is_none")]
+  pub key_id: Option<String>,
+}
+impl JweOptions {
+  /// Cabecera de un JWE con estas opciones.
+  pub fn header(&self) -> JweHeader {
+    JweHeader { kid: self.key_id.clone(), ..JweHeader::new(self.alg, self.enc) }
+  }
+}
+
+/// 📌 Reglas para aceptar la capa de cifrado de un JWE
+///
+/// - `allowed_algorithms` - Los `alg` aceptados; por defecto, cualquiera que
+///   corresponda a la clave.
+/// - `allowed_encryptions` - Los `enc` aceptados; por defecto, todos.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DecryptOptions {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub allowed_algorithms: Option<Vec<JweAlgorithm>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub allowed_encryptions: Option<Vec<ContentEncryption>>,
+}
+impl DecryptOptions {
+  /// Comprueba el `alg` y el `enc` de la cabecera antes de descifrar.
+  pub fn check_header(&self, header: &JweHeader) -> Result<(), JwtError> {
+    if !is_allowed(&self.allowed_algorithms, &header.alg)
+      || !is_allowed(&self.allowed_encryptions, &header.enc)
+    {
+      return Err(JwtError::AlgorithmMismatch);
+    }
+    Ok(())
+  }
+}
+
+/// 📌 Clave capaz de cifrar tokens JWE con el algoritmo indicado
+pub enum EncryptionKey {
+  Dir(Secret<Vec<u8>>),
+  A128KW(Secret<Vec<u8>>),
+  A256KW(Secret<Vec<u8>>),
+  RsaOaep256(RsaPublicKey),
+  EcdhEs(p256::PublicKey),
+  EcdhEsA256KW(p256::PublicKey),
+}
+impl EncryptionKey {
+  /// Construye una clave simétrica (`dir`, `A128KW` o `A256KW`) a partir de
+  /// sus bytes. Con `dir` la longitud debe coincidir con la de `enc`.
+  pub fn from_secret(
+    algorithm: JweAlgorithm,
+    secret: &[u8],
+  ) -> Result<Self, JwtError> {
+    check_symmetric_length(algorithm, secret).map_err(JwtError::InvalidKey)?;
+    let secret = Secret::new(secret.to_vec());
+    match algorithm {
+      JweAlgorithm::Dir => Ok(Self::Dir(secret)),
+      JweAlgorithm::A128KW => Ok(Self::A128KW(secret)),
+      JweAlgorithm::A256KW => Ok(Self::A256KW(secret)),
+      _ => Err(JwtError::InvalidKey(requires_key_message(algorithm))),
+    }
+  }
+
+  /// Importa la clave desde PEM, DER o JWK.
+  ///
+  /// Con `RSA-OAEP-256` y `ECDH-ES` es la clave pública del destinatario;
+  /// también se acepta su clave privada, de la que se usa la parte pública.
+  pub fn from_material(
+    algorithm: JweAlgorithm,
+    material: &KeyMaterial,
+  ) -> Result<Self, JwtError> {
+    Self::import(algorithm, material)
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  fn import(
+    algorithm: JweAlgorithm,
+    material: &KeyMaterial,
+  ) -> Result<Self, Error> {
+    if let KeyMaterial::Jwk(jwk) = material {
+      jwk.check_encryption(algorithm)?;
+    }
+    match algorithm {
+      JweAlgorithm::Dir | JweAlgorithm::A128KW | JweAlgorithm::A256KW => {
+        Ok(Self::from_secret(algorithm, load_symmetric(material)?.expose())?)
+      }
+      JweAlgorithm::RsaOaep256 => {
+        Ok(Self::RsaOaep256(check_rsa_size(material.load_rsa_public(
+          rsa_public_from_pem,
+          rsa_public_from_der,
+          rsa_public_from_components,
+        )?)?))
+      }
+      JweAlgorithm::EcdhEs => Ok(Self::EcdhEs(material.load_ec_public(
+        ec_public_from_pem,
+        ec_public_from_der,
+        ec_public_from_bytes,
+      )?)),
+      JweAlgorithm::EcdhEsA256KW => {
+        Ok(Self::EcdhEsA256KW(material.load_ec_public(
+          ec_public_from_pem,
+          ec_public_from_der,
+          ec_public_from_bytes,
+        )?))
+      }
+    }
+  }
+
+  pub fn algorithm(&self) -> JweAlgorithm {
+    match self {
+      Self::Dir(_) => JweAlgorithm::Dir,
+      Self::A128KW(_) => JweAlgorithm::A128KW,
+      Self::A256KW(_) => JweAlgorithm::A256KW,
+      Self::RsaOaep256(_) => JweAlgorithm::RsaOaep256,
+      Self::EcdhEs(_) => JweAlgorithm::EcdhEs,
+      Self::EcdhEsA256KW(_) => JweAlgorithm::EcdhEsA256KW,
+    }
+  }
+
+  /// Cifra `plaintext` y devuelve el JWE en serialización compacta.
+  ///
+  /// El `alg` de la cabecera debe ser el de la clave; `epk` lo añade la
+  /// propia clave cuando hace falta. `crit`, si lo hay, debe estar bien
+  /// formado.
+  pub fn encrypt(
+    &self,
+    mut header: JweHeader,
+    plaintext: &[u8],
+  ) -> Result<String, JwtError> {
+    critical::check_list(header.crit.as_deref(), &header.extra)
+      .map_err(JwtError::InvalidOptions)?;
+    if header.alg != self.algorithm() {
+      return Err(JwtError::AlgorithmMismatch);
+    }
+    let enc = header.enc;
+    let (cek, encrypted_key) = match self {
+      Self::Dir(key) => {
+        check_cek_length(enc, key.expose())?;
+        (key.clone(), Vec::new())
+      }
+      Self::A128KW(kek) => {
+        let cek = random_key(enc.key_len())?;
+        let wrapped = KekAes128::try_from(kek.expose().as_slice())
+          .and_then(|kek| kek.wrap_vec(cek.expose()));
+        (cek, wrapped.map_err(|err| JwtError::Internal(err.to_string()))?)
+      }
+      Self::A256KW(kek) => {
+        let cek = random_key(enc.key_len())?;
+        let wrapped = wrap_a256(kek.expose(), cek.expose())?;
+        (cek, wrapped)
+      }
+      Self::RsaOaep256(key) => {
+        let cek = random_key(enc.key_len())?;
+        let encrypted = key
+          .encrypt(
+            &mut OsRng,
+            PaddingScheme::new_oaep::<Sha256>(),
+            cek.expose(),
+          )
+          .map_err(|err| JwtError::Internal(err.to_string()))?;
+        (cek, encrypted)
+      }
+      Self::EcdhEs(key) => {
+        let (epk, shared) = ecdh_ephemeral(key)?;
+        header.epk = Some(epk);
+        (concat_kdf(&shared, &header, enc.as_str(), enc.key_len())?, Vec::new())
+      }
+      Self::EcdhEsA256KW(key) => {
+        let (epk, shared) = ecdh_ephemeral(key)?;
+        header.epk = Some(epk);
+        let kek = concat_kdf(&shared, &header, header.alg.as_str(), 32)?;
+        let cek = random_key(enc.key_len())?;
+        let wrapped = wrap_a256(kek.expose(), cek.expose())?;
+        (cek, wrapped)
+      }
+    };
+
+    let header_json = serde_json::to_vec(&header)
+      .map_err(|err| JwtError::Internal(err.to_string()))?;
+    let protected = encode_part(&header_json)?;
+    let iv = random_key(enc.iv_len())?;
+    let (ciphertext, tag) =
+      enc.seal(cek.expose(), iv.expose(), protected.as_bytes(), plaintext)?;
+    Ok(format!(
+      "{protected}.{}.{}.{}.{}",
+      encode_part(&encrypted_key)?,
+      encode_part(iv.expose())?,
+      encode_part(&ciphertext)?,
+      encode_part(&tag)?
+    ))
+  }
+
+  /// Cifra un JWS ya firmado como JWT anidado, con `cty: "JWT"` en la
+  /// cabecera (RFC 7519, 5.2).
+  pub fn encrypt_nested(
+    &self,
+    header: JweHeader,
+    jws: &str,
+  ) -> Result<String, JwtError> {
+    let header = JweHeader { cty: Some("JWT".to_string()), ..header };
+    self.encrypt(header, jws.as_bytes())
+  }
+}
+
+/// 📌 Clave capaz de descifrar tokens JWE con el algoritmo indicado
+pub enum DecryptionKey {
+  Dir(Secret<Vec<u8>>),
+  A128KW(Secret<Vec<u8>>),
+  A256KW(Secret<Vec<u8>>),
+  RsaOaep256(Box<RsaPrivateKey>),
+  EcdhEs(p256::SecretKey),
+  EcdhEsA256KW(p256::SecretKey),
+}
+impl DecryptionKey {
+  /// Construye una clave simétrica (`dir`, `A128KW` o `A256KW`) a partir de
+  /// sus bytes.
+  pub fn from_secret(
+    algorithm: JweAlgorithm,
+    secret: &[u8],
+  ) -> Result<Self, JwtError> {
+    Ok(match EncryptionKey::from_secret(algorithm, secret)? {
+      EncryptionKey::Dir(secret) => Self::Dir(secret),
+      EncryptionKey::A128KW(secret) => Self::A128KW(secret),
+      EncryptionKey::A256KW(secret) => Self::A256KW(secret),
+      _ => unreachable!("from_secret only builds symmetric keys"),
+    })
+  }
+
+  /// Importa la clave desde PEM, DER o JWK. Con `RSA-OAEP-256` y `ECDH-ES`
+  /// es la clave privada del destinatario.
+  pub fn from_material(
+    algorithm: JweAlgorithm,
+    material: &KeyMaterial,
+  ) -> Result<Self, JwtError> {
+    Self::import(algorithm, material)
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  fn import(
+    algorithm: JweAlgorithm,
+    material: &KeyMaterial,
+  ) -> Result<Self, Error> {
+    if let KeyMaterial::Jwk(jwk) = material {
+      jwk.check_encryption(algorithm)?;
+    }
+    match algorithm {
+      JweAlgorithm::Dir | JweAlgorithm::A128KW | JweAlgorithm::A256KW => {
+        Ok(Self::from_secret(algorithm, load_symmetric(material)?.expose())?)
+      }
+      JweAlgorithm::RsaOaep256 => {
+        let key = material
+          .load_rsa_private(rsa_private_from_pem, rsa_private_from_der)?;
+        check_rsa_size(key.to_public_key())?;
+        Ok(Self::RsaOaep256(Box::new(key)))
+      }
+      JweAlgorithm::EcdhEs => Ok(Self::EcdhEs(material.load_ec_private(
+        ec_private_from_pem,
+        ec_private_from_der,
+        ec_private_from_bytes,
+      )?)),
+      JweAlgorithm::EcdhEsA256KW => {
+        Ok(Self::EcdhEsA256KW(material.load_ec_private(
+          ec_private_from_pem,
+          ec_private_from_der,
+          ec_private_from_bytes,
+        )?))
+      }
+    }
+  }
+
+  pub fn algorithm(&self) -> JweAlgorithm {
+    match self {
+      Self::Dir(_) => JweAlgorithm::Dir,
+      Self::A128KW(_) => JweAlgorithm::A128KW,
+      Self::A256KW(_) => JweAlgorithm::A256KW,
+      Self::RsaOaep256(_) => JweAlgorithm::RsaOaep256,
+      Self::EcdhEs(_) => JweAlgorithm::EcdhEs,
+      Self::EcdhEsA256KW(_) => JweAlgorithm::EcdhEsA256KW,
+    }
+  }
+
+  /// Descifra un JWE compacto y devuelve su cabecera y su contenido.
+  ///
+  /// Cualquier fallo criptográfico (clave incorrecta, etiqueta manipulada...)
+  /// se devuelve como `DecryptionFailed`, sin más detalle. Un parámetro de
+  /// `crit` se rechaza con `UnsupportedCritical` antes de usar la clave.
+  pub fn decrypt(&self, token: &str) -> Result<(JweHeader, Vec<u8>), JwtError> {
+    let mut parts = token.split('.');
+    let (
+      Some(protected),
+      Some(encrypted_key),
+      Some(iv),
+      Some(ciphertext),
+      Some(tag),
+      None,
+    ) = (
+      parts.next(),
+      parts.next(),
+      parts.next(),
+      parts.next(),
+      parts.next(),
+      parts.next(),
+    )
+    else {
+      return Err(JwtError::Malformed(
+        "JWE must have five dot-separated parts".to_string(),
+      ));
+    };
+    let header = JweHeader::decode(protected)?;
+    check_critical(&header)?;
+    if header.alg != self.algorithm() {
+      return Err(JwtError::AlgorithmMismatch);
+    }
+    let enc = header.enc;
+    let encrypted_key = decode_part(encrypted_key)?;
+
+    let cek = match self {
+      Self::Dir(key) => {
+        if !encrypted_key.is_empty() {
+          return Err(JwtError::Malformed(
+            "JWE with `dir` must have an empty encrypted key".to_string(),
+          ));
+        }
+        key.clone()
+      }
+      Self::A128KW(kek) => KekAes128::try_from(kek.expose().as_slice())
+        .and_then(|kek| kek.unwrap_vec(&encrypted_key))
+        .map(Secret::new)
+        .map_err(|_| JwtError::DecryptionFailed)?,
+      Self::A256KW(kek) => unwrap_a256(kek.expose(), &encrypted_key)?,
+      Self::RsaOaep256(key) => key
+        .decrypt_blinded(
+          &mut OsRng,
+          PaddingScheme::new_oaep::<Sha256>(),
+          &encrypted_key,
+        )
+        .map(Secret::new)
+        .map_err(|_| JwtError::DecryptionFailed)?,
+      Self::EcdhEs(key) => {
+        let shared = ecdh_static(key, &header)?;
+        concat_kdf(&shared, &header, enc.as_str(), enc.key_len())?
+      }
+      Self::EcdhEsA256KW(key) => {
+        let shared = ecdh_static(key, &header)?;
+        let kek = concat_kdf(&shared, &header, header.alg.as_str(), 32)?;
+        unwrap_a256(kek.expose(), &encrypted_key)?
+      }
+    };
+    if cek.expose().len() != enc.key_len() {
+      return Err(JwtError::DecryptionFailed);
+    }
+
+    let plaintext = enc.open(
+      cek.expose(),
+      &decode_part(iv)?,
+      protected.as_bytes(),
+      &decode_part(ciphertext)?,
+      &decode_part(tag)?,
+    )?;
+    Ok((header, plaintext))
+  }
+
+  /// Igual que `decrypt`, pero antes comprueba la cabecera con `options`.
+  pub fn decrypt_with(
+    &self,
+    token: &str,
+    options: &DecryptOptions,
+  ) -> Result<(JweHeader, Vec<u8>), JwtError> {
+    options.check_header(&JweHeader::decode(token)?)?;
+    self.decrypt(token)
+  }
+
+  /// Descifra un JWT anidado y devuelve el JWS que contiene, todavía sin
+  /// verificar. La cabecera debe declarar `cty: "JWT"`.
+  pub fn decrypt_nested(
+    &self,
+    token: &str,
+    options: &DecryptOptions,
+  ) -> Result<String, JwtError> {
+    let (header, plaintext) = self.decrypt_with(token, options)?;
+    let is_nested =
+      header.cty.as_deref().is_some_and(|cty| cty.eq_ignore_ascii_case("JWT"));
+    if !is_nested {
+      return Err(JwtError::Malformed(
+        "Nested JWT must have the content type `JWT`".to_string(),
+      ));
+    }
+    String::from_utf8(plaintext)
+      .map_err(|err| JwtError::Malformed(err.to_string()))
+  }
+}
+
+/// 📌 Firma los claims y cifra el token firmado (sign-then-encrypt)
+///
+/// `jws_header` es la cabecera del JWS interior, normalmente la de
+/// `JwtOptions::token_header`, y `jwe_header` la del JWE exterior.
+pub fn sign_and_encrypt(
+  claims: JWTClaims<Value>,
+  signing_key: &SigningKey,
+  jws_header: TokenHeader,
+  encryption_key: &EncryptionKey,
+  jwe_header: JweHeader,
+) -> Result<String, JwtError> {
+  let jws = signing_key.sign_with_header(claims, jws_header)?;
+  encryption_key.encrypt_nested(jwe_header, &jws)
+}
+
+/// 📌 Descifra un JWT anidado, verifica la firma del token interno y valida
+/// sus claims (decrypt-then-verify)
+///
+/// Cada capa tiene sus propias reglas: `decrypt_options` para el JWE exterior
+/// y `options` para el JWS interior. La cabecera devuelta es la del JWS.
+pub fn decrypt_and_verify(
+  token: &str,
+  decryption_key: &DecryptionKey,
+  decrypt_options: &DecryptOptions,
+  verifying_key: &VerifyingKey,
+  options: &VerifyOptions,
+) -> Result<VerifiedToken, JwtError> {
+  let jws = decryption_key.decrypt_nested(token, decrypt_options)?;
+  verifying_key.verify_full(&jws, options)
+}
+
+// No hay extensiones críticas de JWE que entender: como en un JWS sin
+// manejador, cualquier parámetro de `crit` hace fallar el token
+fn check_critical(header: &JweHeader) -> Result<(), JwtError> {
+  critical::check_list(header.crit.as_deref(), &header.extra)
+    .map_err(JwtError::Malformed)?;
+  match header.crit.iter().flatten().next() {
+    Some(name) => {
+      Err(JwtError::UnsupportedCritical { parameter: name.to_string() })
+    }
+    None => Ok(()),
+  }
+}
+
+// Sin lista de valores permitidos se acepta cualquiera
+fn is_allowed<T: PartialEq>(allowed: &Option<Vec<T>>, value: &T) -> bool {
+  allowed.as_ref().is_none_or(|allowed| allowed.contains(value))
+}
+
+// Las claves simétricas llegan como bytes en bruto o como JWK `oct`
+fn load_symmetric(material: &KeyMaterial) -> Result<Secret<Vec<u8>>, Error> {
+  match material {
+    KeyMaterial::Der(der) => Ok(der.clone()),
+    KeyMaterial::Jwk(jwk) => jwk.private_member("k", &jwk.k),
+    KeyMaterial::Pem(_) => {
+      Err(Error::msg("Symmetric JWE keys must be a Uint8Array or an oct JWK"))
+    }
+  }
+}
+
+fn check_symmetric_length(
+  algorithm: JweAlgorithm,
+  secret: &[u8],
+) -> Result<(), String> {
+  let expected = match algorithm {
+    JweAlgorithm::A128KW => 16,
+    JweAlgorithm::A256KW => 32,
+    _ if secret.is_empty() => {
+      return Err("Secret cannot be empty".to_string());
+    }
+    _ => return Ok(()),
+  };
+  if secret.len() != expected {
+    return Err(format!("Key for {algorithm} must be {expected} bytes long"));
+  }
+  Ok(())
+}
+
+// Con `dir` la clave compartida es la propia clave de contenido
+fn check_cek_length(
+  enc: ContentEncryption,
+  key: &[u8],
+) -> Result<(), JwtError> {
+  if key.len() != enc.key_len() {
+    return Err(JwtError::InvalidKey(format!(
+      "Key for dir with {enc} must be {} bytes long",
+      enc.key_len()
+    )));
+  }
+  Ok(())
+}
+
+fn check_rsa_size(key: RsaPublicKey) -> Result<RsaPublicKey, Error> {
+  if key.size() * 8 < MIN_RSA_MODULUS_BITS {
+    return Err(JWTError::UnsupportedRSAModulus.into());
+  }
+  Ok(key)
+}
+
+fn rsa_public_from_pem(pem: &str) -> Result<RsaPublicKey, Error> {
+  RsaPublicKey::from_public_key_pem(pem)
+    .ok()
+    .or_else(|| RsaPublicKey::from_pkcs1_pem(pem).ok())
+    .or_else(|| rsa_private_from_pem(pem).ok().map(|key| key.to_public_key()))
+    .ok_or_else(|| JWTError::InvalidPublicKey.into())
+}
+
+fn rsa_public_from_der(der: &[u8]) -> Result<RsaPublicKey, Error> {
+  RsaPublicKey::from_public_key_der(der)
+    .ok()
+    .or_else(|| RsaPublicKey::from_pkcs1_der(der).ok())
+    .or_else(|| rsa_private_from_der(der).ok().map(|key| key.to_public_key()))
+    .ok_or_else(|| JWTError::InvalidPublicKey.into())
+}
+
+fn rsa_public_from_components(
+  n: &[u8],
+  e: &[u8],
+) -> Result<RsaPublicKey, Error> {
+  RsaPublicKey::new(BigUint::from_bytes_be(n), BigUint::from_bytes_be(e))
+    .map_err(|_| JWTError::InvalidPublicKey.into())
+}
+
+fn rsa_private_from_pem(pem: &str) -> Result<RsaPrivateKey, Error> {
+  RsaPrivateKey::from_pkcs8_pem(pem)
+    .ok()
+    .or_else(|| RsaPrivateKey::from_pkcs1_pem(pem).ok())
+    .ok_or_else(|| JWTError::InvalidKeyPair.into())
+}
+
+fn rsa_private_from_der(der: &[u8]) -> Result<RsaPrivateKey, Error> {
+  RsaPrivateKey::from_pkcs8_der(der)
+    .ok()
+    .or_else(|| RsaPrivateKey::from_pkcs1_der(der).ok())
+    .ok_or_else(|| JWTError::InvalidKeyPair.into())
+}
+
+fn ec_public_from_pem(pem: &str) -> Result<p256::PublicKey, Error> {
+  p256::PublicKey::from_public_key_pem(pem)
+    .ok()
+    .or_else(|| ec_private_from_pem(pem).ok().map(|key| key.public_key()))
+    .or_else(|| {
+      p256::SecretKey::from_sec1_pem(pem).ok().map(|key| key.public_key())
+    })
+    .ok_or_else(|| JWTError::InvalidPublicKey.into())
+}
+
+fn ec_public_from_der(der: &[u8]) -> Result<p256::PublicKey, Error> {
+  p256::PublicKey::from_public_key_der(der)
+    .ok()
+    .or_else(|| ec_private_from_der(der).ok().map(|key| key.public_key()))
+    .or_else(|| {
+      p256::SecretKey::from_sec1_der(der).ok().map(|key| key.public_key())
+    })
+    .ok_or_else(|| JWTError::InvalidPublicKey.into())
+}
+
+fn ec_public_from_bytes(bytes: &[u8]) -> Result<p256::PublicKey, Error> {
+  p256::PublicKey::from_sec1_bytes(bytes)
+    .map_err(|_| JWTError::InvalidPublicKey.into())
+}
+
+fn ec_private_from_pem(pem: &str) -> Result<p256::SecretKey, Error> {
+  p256::SecretKey::from_pkcs8_pem(pem)
+    .map_err(|_| JWTError::InvalidKeyPair.into())
+}
+
+fn ec_private_from_der(der: &[u8]) -> Result<p256::SecretKey, Error> {
+  p256::SecretKey::from_pkcs8_der(der)
+    .map_err(|_| JWTError::InvalidKeyPair.into())
+}
+
+fn ec_private_from_bytes(bytes: &[u8]) -> Result<p256::SecretKey, Error> {
+  p256::SecretKey::from_slice(bytes)
+    .map_err(|_| JWTError::InvalidKeyPair.into())
+}
+
+// Acuerdo ECDH con una clave efímera; devuelve su parte pública como `epk`
+fn ecdh_ephemeral(
+  public_key: &p256::PublicKey,
+) -> Result<(Jwk, Secret<Vec<u8>>), JwtError> {
+  let ephemeral = EphemeralSecret::random(&mut OsRng);
+  let shared = ephemeral.diffie_hellman(public_key);
+  let point = ephemeral.public_key().to_encoded_point(false);
+  let epk = Jwk::ec(Algorithm::ES256, point.as_bytes(), None)
+    .map_err(|err| JwtError::Internal(err.to_string()))?;
+  Ok((epk, Secret::new(shared.raw_secret_bytes().to_vec())))
+}
+
+// Acuerdo ECDH con la clave efímera (`epk`) de la cabecera
+fn ecdh_static(
+  secret_key: &p256::SecretKey,
+  header: &JweHeader,
+) -> Result<Secret<Vec<u8>>, JwtError> {
+  let epk = header.epk.as_ref().ok_or_else(|| {
+    JwtError::Malformed("JWE with ECDH-ES must have an `epk`".to_string())
+  })?;
+  if epk.kty != "EC" || epk.crv.as_deref() != Some("P-256") {
+    return Err(JwtError::Malformed(
+      "The `epk` of the JWE must be a P-256 key".to_string(),
+    ));
+  }
+  let point =
+    epk.ec_point().map_err(|err| JwtError::Malformed(err.to_string()))?;
+  // `from_sec1_bytes` rechaza los puntos que no están en la curva
+  let epk = p256::PublicKey::from_sec1_bytes(&point)
+    .map_err(|_| JwtError::Malformed("Invalid `epk` point".to_string()))?;
+  let shared =
+    p256::ecdh::diffie_hellman(secret_key.to_nonzero_scalar(), epk.as_affine());
+  Ok(Secret::new(shared.raw_secret_bytes().to_vec()))
+}
+
+// Concat KDF con SHA-256 (RFC 7518, 4.6.2)
+fn concat_kdf(
+  shared: &Secret<Vec<u8>>,
+  header: &JweHeader,
+  algorithm_id: &str,
+  key_len: usize,
+) -> Result<Secret<Vec<u8>>, JwtError> {
+  let party_info = |value: &Option<String>| match value {
+    Some(value) => decode_part(value),
+    None => Ok(Vec::new()),
+  };
+  let mut other_info = Vec::new();
+  for field in [
+    algorithm_id.as_bytes().to_vec(),
+    party_info(&header.apu)?,
+    party_info(&header.apv)?,
+  ] {
+    other_info.extend((field.len() as u32).to_be_bytes());
+    other_info.extend(field);
+  }
+  other_info.extend(((key_len * 8) as u32).to_be_bytes());
+
+  let mut key = Vec::with_capacity(key_len + 32);
+  let mut counter = 1u32;
+  while key.len() < key_len {
+    let mut hasher = Sha256::new();
+    hasher.update(counter.to_be_bytes());
+    hasher.update(shared.expose());
+    hasher.update(&other_info);
+    key.extend(hasher.finalize());
+    counter += 1;
+  }
+  key.truncate(key_len);
+  Ok(Secret::new(key))
+}
+
+fn wrap_a256(kek: &[u8], cek: &[u8]) -> Result<Vec<u8>, JwtError> {
+  KekAes256::try_from(kek)
+    .and_then(|kek| kek.wrap_vec(cek))
+    .map_err(|err| JwtError::Internal(err.to_string()))
+}
+
+fn unwrap_a256(
+  kek: &[u8],
+  wrapped: &[u8],
+) -> Result<Secret<Vec<u8>>, JwtError> {
+  KekAes256::try_from(kek)
+    .and_then(|kek| kek.unwrap_vec(wrapped))
+    .map(Secret::new)
+    .map_err(|_| JwtError::DecryptionFailed)
+}
+
+// HMAC de A128CBC-HS256 sobre `aad || iv || ciphertext || al`
+fn cbc_hmac(
+  mac_key: &[u8],
+  aad: &[u8],
+  iv: &[u8],
+  ciphertext: &[u8],
+) -> Result<Hmac<Sha256>, hmac::digest::InvalidLength> {
+  let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(mac_key)?;
+  mac.update(aad);
+  mac.update(iv);
+  mac.update(ciphertext);
+  mac.update(&(aad.len() as u64 * 8).to_be_bytes());
+  Ok(mac)
+}
+
+fn random_key(length: usize) -> Result<Secret<Vec<u8>>, JwtError> {
+  let mut key = vec![0u8; length];
+  OsRng
+    .try_fill_bytes(&mut key)
+    .map_err(|err| JwtError::Internal(err.to_string()))?;
+  Ok(Secret::new(key))
+}
+
+fn encode_part(bytes: &[u8]) -> Result<String, JwtError> {
+  Base64UrlSafeNoPadding::encode_to_string(bytes)
+    .map_err(|err| JwtError::Internal(err.to_string()))
+}
+
+fn decode_part(part: &str) -> Result<Vec<u8>, JwtError> {
+  Base64UrlSafeNoPadding::decode_to_vec(part, None)
+    .map_err(|err| JwtError::Malformed(err.to_string()))
+}
+
+fn requires_key_message(algorithm: JweAlgorithm) -> String {
+  format!("Algorithm {algorithm} requires a PEM, DER or JWK key")
+}
@@ -0,0 +1,459 @@
+use crate::algorithm::Algorithm;
+use crate::error::JwtError;
+use crate::jwe::JweAlgorithm;
+use crate::keys::{KeyMaterial, VerifyingKey};
+use crate::secret::{serialize_exposed, Secret};
+use crate::token::TokenHeader;
+use jwt_simple::prelude::*;
+use jwt_simple::{Error, JWTError};
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::{BigUint, PublicKeyParts};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// 📌 Clave en formato JWK (RFC 7517)
+///
+/// Admite claves simétricas (`oct`), RSA, de curva elíptica (`EC`, RFC 7518)
+/// y de curva de Edwards (`OKP`, RFC 8037). Los valores binarios van en
+/// base64url sin relleno, y los miembros privados (`k`, `d`, `p`, `q`, `dp`,
+/// `dq` y `qi`) solo aparecen en las claves privadas.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Jwk {
+  pub kty: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub kid: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub alg: Option<String>,
+  #[serde(default, rename = "use", skip_serializing_if = "Option::is_none")]
+  pub key_use: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub crv: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub x: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub y: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub n: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub e: Option<String>,
+  #[serde(
+    default,
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub d: Option<Secret>,
+  #[serde(
+    default,
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub p: Option<Secret>,
+  #[serde(
+    default,
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub q: Option<Secret>,
+  #[serde(
+    default,
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub dp: Option<Secret>,
+  #[serde(
+    default,
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub dq: Option<Secret>,
+  #[serde(
+    default,
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub qi: Option<Secret>,
+  #[serde(
+    default,
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub k: Option<Secret>,
+}
+impl Jwk {
+  pub fn from_json(json: &str) -> Result<Self, JwtError> {
+    serde_json::from_str(json)
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  pub fn to_json(&self) -> Result<String, JwtError> {
+    serde_json::to_string(self)
+      .map_err(|err| JwtError::Internal(err.to_string()))
+  }
+
+  /// Indica si la clave contiene material privado.
+  pub fn is_private(&self) -> bool {
+    self.d.is_some() || self.k.is_some()
+  }
+
+  /// Devuelve la clave sin sus miembros privados.
+  ///
+  /// Las claves `oct` son secretas por definición y no tienen parte pública.
+  pub fn to_public(&self) -> Result<Self, JwtError> {
+    if self.kty == "oct" {
+      return Err(JwtError::InvalidKey(
+        "Symmetric keys cannot be exported as public keys".to_string(),
+      ));
+    }
+    Ok(Self {
+      d: None,
+      p: None,
+      q: None,
+      dp: None,
+      dq: None,
+      qi: None,
+      ..self.clone()
+    })
+  }
+
+  /// Indica si la clave puede usarse para firmas con el algoritmo indicado.
+  ///
+  /// Se comprueban el tipo de clave y la curva, el `alg` declarado (si lo
+  /// hay) y que el uso (`use`) no sea de cifrado.
+  pub fn supports(&self, algorithm: Algorithm) -> bool {
+    if self.key_use.as_deref().is_some_and(|key_use| key_use != "sig") {
+      return false;
+    }
+    if self.alg.as_deref().is_some_and(|alg| alg != algorithm.as_str()) {
+      return false;
+    }
+    match self.kty.as_str() {
+      "oct" => algorithm.is_hmac(),
+      "RSA" => matches!(
+        algorithm,
+        Algorithm::RS256
+          | Algorithm::RS384
+          | Algorithm::RS512
+          | Algorithm::PS256
+          | Algorithm::PS384
+          | Algorithm::PS512
+      ),
+      "EC" | "OKP" => self.crv.as_deref() == curve(algorithm),
+      _ => false,
+    }
+  }
+
+  // Comprueba que la clave sirve para el algoritmo antes de importarla
+  pub(crate) fn check(&self, algorithm: Algorithm) -> Result<(), Error> {
+    if self.supports(algorithm) {
+      return Ok(());
+    }
+    Err(Error::msg(format!(
+      "JWK of type {} cannot be used with {algorithm}",
+      self.kty
+    )))
+  }
+
+  /// Indica si la clave puede usarse para cifrar tokens JWE con el algoritmo
+  /// de gestión de claves indicado.
+  ///
+  /// Igual que `supports`, pero el uso (`use`), si lo hay, debe ser `enc`.
+  pub fn supports_encryption(&self, algorithm: JweAlgorithm) -> bool {
+    if self.key_use.as_deref().is_some_and(|key_use| key_use != "enc") {
+      return false;
+    }
+    if self.alg.as_deref().is_some_and(|alg| alg != algorithm.as_str()) {
+      return false;
+    }
+    match self.kty.as_str() {
+      "oct" => matches!(
+        algorithm,
+        JweAlgorithm::Dir | JweAlgorithm::A128KW | JweAlgorithm::A256KW
+      ),
+      "RSA" => algorithm == JweAlgorithm::RsaOaep256,
+      "EC" => {
+        matches!(algorithm, JweAlgorithm::EcdhEs | JweAlgorithm::EcdhEsA256KW)
+          && self.crv.as_deref() == Some("P-256")
+      }
+      _ => false,
+    }
+  }
+
+  pub(crate) fn check_encryption(
+    &self,
+    algorithm: JweAlgorithm,
+  ) -> Result<(), Error> {
+    if self.supports_encryption(algorithm) {
+      return Ok(());
+    }
+    Err(Error::msg(format!(
+      "JWK of type {} cannot be used with {algorithm}",
+      self.kty
+    )))
+  }
+
+  // Compara la parte pública de dos claves ignorando los ceros a la izquierda
+  pub(crate) fn same_public_key(&self, other: &Self) -> Result<bool, Error> {
+    for (name, ours, theirs) in [
+      ("x", &self.x, &other.x),
+      ("y", &self.y, &other.y),
+      ("n", &self.n, &other.n),
+      ("e", &self.e, &other.e),
+    ] {
+      if ours.is_none() && theirs.is_none() {
+        continue;
+      }
+      let ours = member(name, ours)?;
+      let theirs = member(name, theirs)?;
+      if trim_leading_zeros(&ours) != trim_leading_zeros(&theirs) {
+        return Ok(false);
+      }
+    }
+    Ok(true)
+  }
+
+  pub(crate) fn oct(k: &[u8]) -> Result<Self, Error> {
+    Ok(Self {
+      kty: "oct".to_string(),
+      k: Some(encode_secret(k)?),
+      ..Default::default()
+    })
+  }
+
+  pub(crate) fn rsa(n: &[u8], e: &[u8]) -> Result<Self, Error> {
+    Ok(Self {
+      kty: "RSA".to_string(),
+      n: Some(encode(n)?),
+      e: Some(encode(e)?),
+      ..Default::default()
+    })
+  }
+
+  // `point` es el punto público sin comprimir (`0x04 || x || y`)
+  pub(crate) fn ec(
+    algorithm: Algorithm,
+    point: &[u8],
+    d: Option<&[u8]>,
+  ) -> Result<Self, Error> {
+    let coordinates = point.get(1..).ok_or(JWTError::InvalidPublicKey)?;
+    let (x, y) = coordinates.split_at(coordinates.len() / 2);
+    Ok(Self {
+      kty: "EC".to_string(),
+      crv: curve(algorithm).map(str::to_string),
+      x: Some(encode(x)?),
+      y: Some(encode(y)?),
+      d: d.map(encode_secret).transpose()?,
+      ..Default::default()
+    })
+  }
+
+  pub(crate) fn okp(x: &[u8], d: Option<&[u8]>) -> Result<Self, Error> {
+    Ok(Self {
+      kty: "OKP".to_string(),
+      crv: Some("Ed25519".to_string()),
+      x: Some(encode(x)?),
+      d: d.map(encode_secret).transpose()?,
+      ..Default::default()
+    })
+  }
+
+  // Vincula la clave exportada a su algoritmo y a un uso de firma
+  pub(crate) fn bound_to(mut self, algorithm: Algorithm) -> Self {
+    self.alg = Some(algorithm.to_string());
+    self.key_use = Some("sig".to_string());
+    self
+  }
+
+  pub(crate) fn with_key_id(mut self, kid: Option<String>) -> Self {
+    if kid.is_some() {
+      self.kid = kid;
+    }
+    self
+  }
+
+  // Clave RSA privada completa, con los parámetros CRT si están disponibles
+  pub(crate) fn rsa_private(key: &rsa::RsaPrivateKey) -> Result<Self, Error> {
+    let [p, q] = key.primes() else {
+      return Err(Error::msg("Multi-prime RSA keys are not supported"));
+    };
+    let optional = |value: Option<BigUint>| {
+      value.map(|value| encode_secret(&value.to_bytes_be())).transpose()
+    };
+    Ok(Self {
+      d: Some(encode_secret(&key.d().to_bytes_be())?),
+      p: Some(encode_secret(&p.to_bytes_be())?),
+      q: Some(encode_secret(&q.to_bytes_be())?),
+      dp: optional(key.dp().cloned())?,
+      dq: optional(key.dq().cloned())?,
+      qi: optional(key.crt_coefficient())?,
+      ..Self::rsa(&key.n().to_bytes_be(), &key.e().to_bytes_be())?
+    })
+  }
+
+  // Reconstruye la clave RSA privada y la codifica en DER (PKCS#1)
+  pub(crate) fn rsa_private_der(&self) -> Result<Secret<Vec<u8>>, Error> {
+    let public = |name, value| {
+      member(name, value).map(|bytes| BigUint::from_bytes_be(&bytes))
+    };
+    let private = |name, value| {
+      private_member(name, value)
+        .map(|bytes| BigUint::from_bytes_be(bytes.expose()))
+    };
+    let key = rsa::RsaPrivateKey::from_components(
+      public("n", &self.n)?,
+      public("e", &self.e)?,
+      private("d", &self.d)?,
+      vec![private("p", &self.p)?, private("q", &self.q)?],
+    )
+    .map_err(|_| JWTError::InvalidKeyPair)?;
+    let der = key.to_pkcs1_der().map_err(|_| JWTError::InvalidKeyPair)?;
+    Ok(Secret::new(der.as_bytes().to_vec()))
+  }
+
+  // Decodifica un miembro público obligatorio
+  pub(crate) fn member(
+    &self,
+    name: &str,
+    value: &Option<String>,
+  ) -> Result<Vec<u8>, Error> {
+    member(name, value)
+  }
+
+  // Decodifica un miembro privado obligatorio
+  pub(crate) fn private_member(
+    &self,
+    name: &str,
+    value: &Option<Secret>,
+  ) -> Result<Secret<Vec<u8>>, Error> {
+    private_member(name, value)
+  }
+
+  // Punto público sin comprimir de una clave EC
+  pub(crate) fn ec_point(&self) -> Result<Vec<u8>, Error> {
+    let mut point = vec![0x04];
+    point.extend(member("x", &self.x)?);
+    point.extend(member("y", &self.y)?);
+    Ok(point)
+  }
+}
+
+/// 📌 Conjunto de claves JWK (JWKS)
+///
+/// Se construye a partir del documento JSON que publican los proveedores de
+/// identidad (`{ "keys": [...] }`) y elige la clave de cada token por su
+/// `kid` y su `alg`.
+///
+/// ```typescript
+/// export class JwkSet {
+///   constructor(jwks: string | { keys: Jwk[] });
+///   verify(token: string, options?: VerifyOptions): Map<string, any>;
+///   verify_full(token: string, options?: VerifyOptions): VerifiedToken;
+///   to_json(): string;
+/// }
+/// ```
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct JwkSet {
+  keys: Vec<Jwk>,
+}
+impl JwkSet {
+  pub fn new(keys: Vec<Jwk>) -> Self {
+    Self { keys }
+  }
+
+  pub fn from_json(json: &str) -> Result<Self, JwtError> {
+    serde_json::from_str(json)
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  pub fn keys(&self) -> &[Jwk] {
+    &self.keys
+  }
+
+  /// Devuelve el conjunto sin los miembros privados de sus claves. Las
+  /// claves `oct` no tienen parte pública, así que se omiten.
+  pub fn to_public(&self) -> Self {
+    let keys = self
+      .keys
+      .iter()
+      // `Jwk::to_public` solo falla con las claves `oct`
+      .filter_map(|jwk| jwk.to_public().ok())
+      .collect();
+    Self { keys }
+  }
+
+  /// Busca la clave con el `kid` indicado que admita el algoritmo.
+  ///
+  /// Sin `kid` se elige la primera clave compatible con el algoritmo.
+  pub fn find(
+    &self,
+    kid: Option<&str>,
+    algorithm: Algorithm,
+  ) -> Result<&Jwk, JwtError> {
+    let mut candidates = self
+      .keys
+      .iter()
+      .filter(|jwk| kid.is_none() || jwk.kid.as_deref() == kid)
+      .peekable();
+    if candidates.peek().is_none() {
+      return Err(match kid {
+        Some(_) => JwtError::KeyIdMismatch,
+        None => JwtError::InvalidKey("The JWKS has no keys".to_string()),
+      });
+    }
+    candidates
+      .find(|jwk| jwk.supports(algorithm))
+      .ok_or(JwtError::AlgorithmMismatch)
+  }
+
+  /// Importa la clave con la que se debe verificar el token según su
+  /// cabecera (`kid` y `alg`).
+  pub fn verifying_key(&self, token: &str) -> Result<VerifyingKey, JwtError> {
+    let header = TokenHeader::decode(token)?;
+    let algorithm: Algorithm = header.alg.parse()?;
+    let jwk = self.find(header.kid.as_deref(), algorithm)?;
+    VerifyingKey::from_material(
+      algorithm,
+      &KeyMaterial::Jwk(Box::new(jwk.clone())),
+    )
+  }
+}
+// Curva que corresponde a cada algoritmo de curva elíptica o de Edwards
+fn curve(algorithm: Algorithm) -> Option<&'static str> {
+  match algorithm {
+    Algorithm::ES256 => Some("P-256"),
+    Algorithm::ES384 => Some("P-384"),
+    Algorithm::ES256K => Some("secp256k1"),
+    Algorithm::EdDSA => Some("Ed25519"),
+    _ => None,
+  }
+}
+
+fn member(name: &str, value: &Option<String>) -> Result<Vec<u8>, Error> {
+  let value = value
+    .as_deref()
+    .ok_or_else(|| Error::msg(format!("JWK is missing the `{name}` member")))?;
+  Ok(Base64UrlSafeNoPadding::decode_to_vec(value, None)?)
+}
+
+fn private_member(
+  name: &str,
+  value: &Option<Secret>,
+) -> Result<Secret<Vec<u8>>, Error> {
+  let value = value
+    .as_ref()
+    .ok_or_else(|| Error::msg(format!("JWK is missing the `{name}` member")))?;
+  Ok(Secret::new(Base64UrlSafeNoPadding::decode_to_vec(value.expose(), None)?))
+}
+
+fn encode(bytes: &[u8]) -> Result<String, Error> {
+  Ok(Base64UrlSafeNoPadding::encode_to_string(bytes)?)
+}
+
+fn encode_secret(bytes: &[u8]) -> Result<Secret, Error> {
+  encode(bytes).map(Secret::new)
+}
+
+fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+  let start = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len());
+  &bytes[start..]
+}
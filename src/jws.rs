@@ -0,0 +1,343 @@
+use crate::critical;
+use crate::error::JwtError;
+use crate::keys::{SigningKey, VerifyingKey};
+use crate::token::{decode_part, TokenHeader};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use jwt_simple::prelude::*;
+use p256::ecdsa::signature::{Signer, Verifier};
+use rand_core::OsRng;
+use rsa::{PaddingScheme, PublicKey, RsaPrivateKey, RsaPublicKey};
+use serde_json::Value;
+use sha2::digest::{const_oid::AssociatedOid, DynDigest};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// 📌 Firma los claims con la cabecera indicada (JWS compacto)
+///
+/// `alg` lo pone siempre la clave, y también `kid` si la clave tiene uno.
+pub(crate) fn encode(
+  key: &SigningKey,
+  header: TokenHeader,
+  claims: &JWTClaims<Value>,
+) -> Result<String, JwtError> {
+  let signing_input =
+    format!("{}.{}", encode_header(key, header)?, encode_json(claims)?);
+  let signature = key.sign_bytes(signing_input.as_bytes())?;
+  Ok(format!("{signing_input}.{}", encode_part(&signature)?))
+}
+
+/// 📌 Firma un payload arbitrario sin codificarlo y lo deja fuera del token
+/// (RFC 7797)
+///
+/// Añade `b64: false` a la cabecera, y `b64` a `crit`. El token tiene la
+/// forma `cabecera..firma`.
+pub(crate) fn encode_detached(
+  key: &SigningKey,
+  mut header: TokenHeader,
+  payload: &[u8],
+) -> Result<String, JwtError> {
+  header.extra.insert(B64.to_string(), Value::Bool(false));
+  let crit = header.crit.get_or_insert_with(Vec::new);
+  if !crit.iter().any(|name| name == B64) {
+    crit.push(B64.to_string());
+  }
+  let header_b64 = encode_header(key, header)?;
+  let mut signing_input = format!("{header_b64}.").into_bytes();
+  signing_input.extend_from_slice(payload);
+  let signature = key.sign_bytes(&signing_input)?;
+  Ok(format!("{header_b64}..{}", encode_part(&signature)?))
+}
+
+/// 📌 Verifica la firma de un JWS compacto y devuelve su cabecera y sus
+/// claims, todavía sin validar
+pub(crate) fn decode(
+  key: &VerifyingKey,
+  token: &str,
+) -> Result<(TokenHeader, JWTClaims<Value>), JwtError> {
+  let (header, [header_b64, claims_b64, _], signature) = split(key, token)?;
+  let signing_input = &token[..header_b64.len() + 1 + claims_b64.len()];
+  key.verify_bytes(signing_input.as_bytes(), &signature)?;
+  Ok((header, decode_part(claims_b64)?))
+}
+
+/// 📌 Verifica la firma de un JWS con el payload separado y devuelve su
+/// cabecera
+///
+/// El segmento del payload debe estar vacío. Con `b64: false` (que entonces
+/// debe estar en `crit`) el payload se firma tal cual; si no, codificado en
+/// base64url.
+pub(crate) fn decode_detached(
+  key: &VerifyingKey,
+  token: &str,
+  payload: &[u8],
+) -> Result<TokenHeader, JwtError> {
+  let (header, [header_b64, payload_b64, _], signature) = split(key, token)?;
+  if !payload_b64.is_empty() {
+    return Err(JwtError::Malformed(
+      "A detached JWS must have an empty payload segment".to_string(),
+    ));
+  }
+  let encoded = match header.extra.get(B64) {
+    None => true,
+    Some(Value::Bool(encoded)) => *encoded,
+    Some(_) => {
+      return Err(JwtError::Malformed("`b64` must be a boolean".to_string()))
+    }
+  };
+  let is_critical = header.crit.iter().flatten().any(|name| name == B64);
+  if !encoded && !is_critical {
+    return Err(JwtError::Malformed(
+      "`b64` must be listed in `crit`".to_string(),
+    ));
+  }
+
+  let mut signing_input = format!("{header_b64}.").into_bytes();
+  if encoded {
+    signing_input.extend_from_slice(encode_part(payload)?.as_bytes());
+  } else {
+    signing_input.extend_from_slice(payload);
+  }
+  key.verify_bytes(&signing_input, &signature)?;
+  Ok(header)
+}
+
+// Parámetro de la RFC 7797 que indica si el payload va codificado
+pub(crate) const B64: &str = "b64";
+
+// Fija `alg` y `kid` con los de la clave, comprueba `crit` y codifica la
+// cabecera
+fn encode_header(
+  key: &SigningKey,
+  mut header: TokenHeader,
+) -> Result<String, JwtError> {
+  critical::check_names(&header).map_err(JwtError::InvalidOptions)?;
+  header.alg = key.algorithm().to_string();
+  if let Some(kid) = key.key_id() {
+    header.kid = Some(kid.to_string());
+  }
+  encode_json(&header)
+}
+
+// Separa los tres segmentos, lee la cabecera, comprueba que su `alg` es el
+// de la clave y decodifica la firma
+fn split<'a>(
+  key: &VerifyingKey,
+  token: &'a str,
+) -> Result<(TokenHeader, [&'a str; 3], Vec<u8>), JwtError> {
+  let mut parts = token.split('.');
+  let (Some(header_b64), Some(payload_b64), Some(signature_b64), None) =
+    (parts.next(), parts.next(), parts.next(), parts.next())
+  else {
+    return Err(JwtError::Malformed(
+      "Token must have three dot-separated parts".to_string(),
+    ));
+  };
+  let header = TokenHeader::decode(token)?;
+  if header.alg != key.algorithm().as_str() {
+    return Err(JwtError::AlgorithmMismatch);
+  }
+  let signature = Base64UrlSafeNoPadding::decode_to_vec(signature_b64, None)
+    .map_err(|err| JwtError::Malformed(err.to_string()))?;
+  Ok((header, [header_b64, payload_b64, signature_b64], signature))
+}
+
+impl SigningKey {
+  /// `kid` con el que la clave firma los tokens, si lo tiene.
+  pub fn key_id(&self) -> Option<&str> {
+    let kid = match self {
+      Self::HS256(key) => key.key_id(),
+      Self::HS384(key) => key.key_id(),
+      Self::HS512(key) => key.key_id(),
+      Self::RS256(key) => key.key_id(),
+      Self::RS384(key) => key.key_id(),
+      Self::RS512(key) => key.key_id(),
+      Self::PS256(key) => key.key_id(),
+      Self::PS384(key) => key.key_id(),
+      Self::PS512(key) => key.key_id(),
+      Self::ES256(key) => key.key_id(),
+      Self::ES384(key) => key.key_id(),
+      Self::ES256K(key) => key.key_id(),
+      Self::EdDSA(key) => key.key_id(),
+    };
+    kid.as_deref()
+  }
+
+  // Firma del JWS sobre `BASE64URL(cabecera).BASE64URL(claims)`
+  pub(crate) fn sign_bytes(&self, message: &[u8]) -> Result<Vec<u8>, JwtError> {
+    let signature = match self {
+      Self::HS256(key) => hmac::<Hmac<Sha256>>(key.key().as_ref(), message),
+      Self::HS384(key) => hmac::<Hmac<Sha384>>(key.key().as_ref(), message),
+      Self::HS512(key) => hmac::<Hmac<Sha512>>(key.key().as_ref(), message),
+      Self::RS256(key) => rsa_sign::<Sha256>(key.key_pair().as_ref(), message)?,
+      Self::RS384(key) => rsa_sign::<Sha384>(key.key_pair().as_ref(), message)?,
+      Self::RS512(key) => rsa_sign::<Sha512>(key.key_pair().as_ref(), message)?,
+      Self::PS256(key) => pss_sign::<Sha256>(key.key_pair().as_ref(), message)?,
+      Self::PS384(key) => pss_sign::<Sha384>(key.key_pair().as_ref(), message)?,
+      Self::PS512(key) => pss_sign::<Sha512>(key.key_pair().as_ref(), message)?,
+      Self::ES256(key) => {
+        let signature: p256::ecdsa::Signature =
+          key.key_pair().as_ref().sign(message);
+        signature.to_vec()
+      }
+      Self::ES384(key) => {
+        let signature: p384::ecdsa::Signature =
+          key.key_pair().as_ref().sign(message);
+        signature.to_vec()
+      }
+      Self::ES256K(key) => {
+        let signature: k256::ecdsa::Signature =
+          key.key_pair().as_ref().sign(message);
+        signature.to_vec()
+      }
+      Self::EdDSA(key) => {
+        let noise = ed25519_compact::Noise::generate();
+        key.key_pair().as_ref().sk.sign(message, Some(noise)).to_vec()
+      }
+    };
+    Ok(signature)
+  }
+}
+
+impl VerifyingKey {
+  // Comprueba la firma del JWS; cualquier fallo es `InvalidSignature`
+  pub(crate) fn verify_bytes(
+    &self,
+    message: &[u8],
+    signature: &[u8],
+  ) -> Result<(), JwtError> {
+    let valid = match self {
+      Self::HS256(key) => {
+        hmac_verify::<Hmac<Sha256>>(key.key().as_ref(), message, signature)
+      }
+      Self::HS384(key) => {
+        hmac_verify::<Hmac<Sha384>>(key.key().as_ref(), message, signature)
+      }
+      Self::HS512(key) => {
+        hmac_verify::<Hmac<Sha512>>(key.key().as_ref(), message, signature)
+      }
+      Self::RS256(key) => {
+        rsa_verify::<Sha256>(key.public_key().as_ref(), message, signature)
+      }
+      Self::RS384(key) => {
+        rsa_verify::<Sha384>(key.public_key().as_ref(), message, signature)
+      }
+      Self::RS512(key) => {
+        rsa_verify::<Sha512>(key.public_key().as_ref(), message, signature)
+      }
+      Self::PS256(key) => {
+        pss_verify::<Sha256>(key.public_key().as_ref(), message, signature)
+      }
+      Self::PS384(key) => {
+        pss_verify::<Sha384>(key.public_key().as_ref(), message, signature)
+      }
+      Self::PS512(key) => {
+        pss_verify::<Sha512>(key.public_key().as_ref(), message, signature)
+      }
+      Self::ES256(key) => p256::ecdsa::Signature::try_from(signature)
+        .is_ok_and(|signature| {
+          key.public_key().as_ref().verify(message, &signature).is_ok()
+        }),
+      Self::ES384(key) => p384::ecdsa::Signature::try_from(signature)
+        .is_ok_and(|signature| {
+          key.public_key().as_ref().verify(message, &signature).is_ok()
+        }),
+      Self::ES256K(key) => k256::ecdsa::Signature::try_from(signature)
+        .is_ok_and(|signature| {
+          key.public_key().as_ref().verify(message, &signature).is_ok()
+        }),
+      Self::EdDSA(key) => ed25519_compact::Signature::from_slice(signature)
+        .is_ok_and(|signature| {
+          key.public_key().as_ref().verify(message, &signature).is_ok()
+        }),
+    };
+    if !valid {
+      return Err(JwtError::InvalidSignature);
+    }
+    Ok(())
+  }
+}
+
+fn encode_part(bytes: &[u8]) -> Result<String, JwtError> {
+  Base64UrlSafeNoPadding::encode_to_string(bytes)
+    .map_err(|err| JwtError::Internal(err.to_string()))
+}
+
+fn encode_json<T: Serialize>(value: &T) -> Result<String, JwtError> {
+  let json = serde_json::to_vec(value)
+    .map_err(|err| JwtError::Internal(err.to_string()))?;
+  encode_part(&json)
+}
+
+fn hmac<M: Mac + KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+  let mut mac =
+    <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+  mac.update(message);
+  mac.finalize().into_bytes().to_vec()
+}
+
+// Comparación en tiempo constante
+fn hmac_verify<M: Mac + KeyInit>(
+  key: &[u8],
+  message: &[u8],
+  signature: &[u8],
+) -> bool {
+  let Ok(mut mac) = <M as Mac>::new_from_slice(key) else {
+    return false;
+  };
+  mac.update(message);
+  mac.verify_slice(signature).is_ok()
+}
+
+// RSASSA-PKCS1-v1_5, con blinding como hace `jwt-simple`
+fn rsa_sign<D: Digest + AssociatedOid>(
+  key: &RsaPrivateKey,
+  message: &[u8],
+) -> Result<Vec<u8>, JwtError> {
+  key
+    .sign_blinded(
+      &mut OsRng,
+      PaddingScheme::new_pkcs1v15_sign::<D>(),
+      &D::digest(message),
+    )
+    .map_err(|err| JwtError::Internal(err.to_string()))
+}
+
+fn rsa_verify<D: Digest + AssociatedOid>(
+  key: &RsaPublicKey,
+  message: &[u8],
+  signature: &[u8],
+) -> bool {
+  let digest = D::digest(message);
+  key
+    .verify(PaddingScheme::new_pkcs1v15_sign::<D>(), &digest, signature)
+    .is_ok()
+}
+
+// RSASSA-PSS con MGF1 y una sal del tamaño del hash (RFC 7518, 3.5)
+fn pss_sign<D: Digest + DynDigest + Send + Sync + 'static>(
+  key: &RsaPrivateKey,
+  message: &[u8],
+) -> Result<Vec<u8>, JwtError> {
+  let salt_len = <D as Digest>::output_size();
+  key
+    .sign_blinded(
+      &mut OsRng,
+      PaddingScheme::new_pss_with_salt::<D>(salt_len),
+      &D::digest(message),
+    )
+    .map_err(|err| JwtError::Internal(err.to_string()))
+}
+
+// Acepta también otros tamaños de sal, igual que `jwt-simple`
+fn pss_verify<D: Digest + DynDigest + Send + Sync + 'static>(
+  key: &RsaPublicKey,
+  message: &[u8],
+  signature: &[u8],
+) -> bool {
+  let digest = D::digest(message);
+  let salt_len = <D as Digest>::output_size();
+  key
+    .verify(PaddingScheme::new_pss_with_salt::<D>(salt_len), &digest, signature)
+    .or_else(|_| key.verify(PaddingScheme::new_pss::<D>(), &digest, signature))
+    .is_ok()
+}
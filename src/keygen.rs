@@ -0,0 +1,117 @@
+use crate::algorithm::Algorithm;
+use crate::error::JwtError;
+use crate::jwk::Jwk;
+use crate::keys::SigningKey;
+use crate::secret::{serialize_exposed, Secret};
+use jwt_simple::prelude::*;
+use zeroize::Zeroize;
+
+/// 📌 Opciones para generar claves
+///
+/// - `kid` - Identificador que se añade a los JWK generados.
+/// - `secret_length` - Bytes aleatorios del secreto HMAC; por defecto, la
+///   longitud del hash del algoritmo (32, 48 o 64).
+/// - `modulus_bits` - Tamaño del módulo RSA: 2048 (por defecto), 3072 o 4096.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct KeyGenOptions {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub kid: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub secret_length: Option<usize>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub modulus_bits: Option<usize>,
+}
+
+/// 📌 Clave exportada en PEM (si el tipo de clave lo admite) y en JWK
+#[derive(Serialize, Debug, Clone)]
+pub struct ExportedKey {
+  #[serde(
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub pem: Option<Secret>,
+  pub jwk: Jwk,
+}
+
+/// 📌 Clave recién generada
+///
+/// Para HMAC, `secret` es el secreto listo para usar en `JwtOptions` y en
+/// `verify_jwt`, y no hay clave pública. Para el resto de algoritmos se
+/// devuelven la clave privada y la pública.
+#[derive(Serialize, Debug, Clone)]
+pub struct GeneratedKey {
+  pub algorithm: Algorithm,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub kid: Option<String>,
+  #[serde(
+    serialize_with = "serialize_exposed",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub secret: Option<Secret>,
+  pub private_key: ExportedKey,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub public_key: Option<ExportedKey>,
+}
+impl GeneratedKey {
+  pub fn generate(
+    algorithm: Algorithm,
+    options: &KeyGenOptions,
+  ) -> Result<Self, JwtError> {
+    let kid = options.kid.clone();
+    if algorithm.is_hmac() {
+      let length = options.secret_length.unwrap_or(algorithm.hash_len());
+      let secret = Secret::new(generate_secret(length)?);
+      let key = SigningKey::from_secret(algorithm, secret.expose().as_bytes())?;
+      return Ok(Self {
+        algorithm,
+        secret: Some(secret),
+        private_key: ExportedKey {
+          pem: None,
+          jwk: key.to_jwk()?.with_key_id(kid.clone()),
+        },
+        public_key: None,
+        kid,
+      });
+    }
+
+    let key = SigningKey::generate(algorithm, options.modulus_bits)?;
+    let public_key = key.verifying_key();
+    Ok(Self {
+      algorithm,
+      secret: None,
+      private_key: ExportedKey {
+        pem: Some(key.to_pem()?.into()),
+        jwk: key.to_jwk()?.with_key_id(kid.clone()),
+      },
+      public_key: Some(ExportedKey {
+        pem: Some(public_key.to_pem()?.into()),
+        jwk: public_key.to_jwk()?.with_key_id(kid.clone()),
+      }),
+      kid,
+    })
+  }
+}
+
+/// 📌 Genera un secreto con `length` bytes aleatorios, codificado en base64url
+pub fn generate_secret(length: usize) -> Result<String, JwtError> {
+  if length == 0 {
+    return Err(JwtError::InvalidOptions(
+      "Secret length must be greater than zero".to_string(),
+    ));
+  }
+  random_base64url(length)
+}
+
+// Bytes aleatorios del generador del sistema (`crypto.getRandomValues` en JS)
+pub(crate) fn random_base64url(length: usize) -> Result<String, JwtError> {
+  let mut raw = vec![0u8; length];
+  let encoded = getrandom::getrandom(&mut raw)
+    .map_err(|err| JwtError::Internal(err.to_string()))
+    .and_then(|()| {
+      Base64UrlSafeNoPadding::encode_to_string(&raw)
+        .map_err(|err| JwtError::Internal(err.to_string()))
+    });
+  // Los bytes en bruto son el propio secreto
+  raw.zeroize();
+  encoded
+}
@@ -0,0 +1,243 @@
+use crate::{
+  Algorithm, JwtError, KeyMaterial, SigningKey, TokenHeader, VerifiedToken,
+  VerifyOptions, VerifyingKey,
+};
+use jwt_simple::prelude::*;
+use serde_json::Value;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// 📌 Clave de un `KeyRing` con su identificador y su periodo de validez
+///
+/// `not_before` y `not_after` son segundos desde la época Unix. Las claves
+/// que solo tienen parte pública sirven para verificar, pero nunca para
+/// firmar.
+pub struct KeyRingEntry {
+  kid: String,
+  signing_key: Option<SigningKey>,
+  verifying_key: VerifyingKey,
+  not_before: Option<u64>,
+  not_after: Option<u64>,
+}
+impl KeyRingEntry {
+  pub fn kid(&self) -> &str {
+    &self.kid
+  }
+
+  pub fn algorithm(&self) -> Algorithm {
+    self.verifying_key.algorithm()
+  }
+
+  pub fn not_before(&self) -> Option<u64> {
+    self.not_before
+  }
+
+  pub fn not_after(&self) -> Option<u64> {
+    self.not_after
+  }
+
+  // La clave firma solo dentro de su periodo de validez
+  fn is_active(&self, now: u64) -> bool {
+    self.signing_key.is_some()
+      && self.not_before.is_none_or(|not_before| not_before <= now)
+      && self.not_after.is_none_or(|not_after| now < not_after)
+  }
+}
+
+/// 📌 Conjunto de claves para rotarlas sin cortes
+///
+/// Cada clave tiene un `kid`, un algoritmo y, opcionalmente, un periodo de
+/// validez. Los tokens se firman con la clave activa más reciente, que añade
+/// su `kid` a la cabecera, y se verifican con la clave de ese `kid`. Una
+/// clave retirada (pasado su `not_after`) se sigue aceptando para verificar
+/// durante el periodo de gracia.
+///
+/// ```typescript
+/// export class KeyRing {
+///   constructor(grace_period?: number | string);
+///   add_key(kid: string, algorithm: string, key: string | Uint8Array | Jwk, not_before?: bigint, not_after?: bigint): void;
+///   remove_key(kid: string): boolean;
+///   get_active_key_id(): string;
+///   sign(payload: Record<string, any>, options: JwtOptions): string;
+///   verify(token: string, options?: VerifyOptions): Map<string, any>;
+///   verify_full(token: string, options?: VerifyOptions): VerifiedToken;
+/// }
+/// ```
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Default)]
+pub struct KeyRing {
+  keys: Vec<KeyRingEntry>,
+  grace_period: u64,
+}
+impl KeyRing {
+  /// Crea un `KeyRing` vacío con el periodo de gracia en milisegundos.
+  pub fn with_grace_period(grace_period: u64) -> Self {
+    Self { keys: Vec::new(), grace_period }
+  }
+
+  pub fn keys(&self) -> &[KeyRingEntry] {
+    &self.keys
+  }
+
+  /// Añade una clave capaz de firmar. Los tokens que firme llevarán su `kid`.
+  pub fn add_signing_key(
+    &mut self,
+    kid: &str,
+    key: SigningKey,
+    not_before: Option<u64>,
+    not_after: Option<u64>,
+  ) -> Result<(), JwtError> {
+    let key = key.with_key_id(kid);
+    let verifying_key = key.verifying_key();
+    self.insert(KeyRingEntry {
+      kid: kid.to_string(),
+      signing_key: Some(key),
+      verifying_key,
+      not_before,
+      not_after,
+    })
+  }
+
+  /// Añade una clave que solo sirve para verificar.
+  pub fn add_verifying_key(
+    &mut self,
+    kid: &str,
+    key: VerifyingKey,
+    not_before: Option<u64>,
+    not_after: Option<u64>,
+  ) -> Result<(), JwtError> {
+    self.insert(KeyRingEntry {
+      kid: kid.to_string(),
+      signing_key: None,
+      verifying_key: key,
+      not_before,
+      not_after,
+    })
+  }
+
+  /// Añade la clave privada (para firmar y verificar) o la pública (solo para
+  /// verificar) en PEM, DER o JWK.
+  ///
+  /// Solo el material público se añade como clave de verificación: si trae
+  /// la parte privada y no se puede importar, se devuelve ese error.
+  pub fn add_material(
+    &mut self,
+    kid: &str,
+    algorithm: Algorithm,
+    material: &KeyMaterial,
+    not_before: Option<u64>,
+    not_after: Option<u64>,
+  ) -> Result<(), JwtError> {
+    let error = match SigningKey::from_material(algorithm, material) {
+      Ok(key) => return self.add_signing_key(kid, key, not_before, not_after),
+      Err(err) => err,
+    };
+    let key = match material {
+      KeyMaterial::Pem(pem) if pem.expose().contains("PRIVATE KEY") => {
+        return Err(error)
+      }
+      KeyMaterial::Jwk(jwk) if jwk.is_private() => return Err(error),
+      // Un DER no indica su tipo: es público si se importa como tal
+      KeyMaterial::Der(_) => {
+        VerifyingKey::from_material(algorithm, material).map_err(|_| error)?
+      }
+      _ => VerifyingKey::from_material(algorithm, material)?,
+    };
+    self.add_verifying_key(kid, key, not_before, not_after)
+  }
+
+  pub fn remove(&mut self, kid: &str) -> bool {
+    let len = self.keys.len();
+    self.keys.retain(|entry| entry.kid != kid);
+    self.keys.len() != len
+  }
+
+  /// Clave con la que se firma ahora: de las que están en su periodo de
+  /// validez, la que empezó a serlo más tarde.
+  pub fn active_key(&self) -> Result<&KeyRingEntry, JwtError> {
+    let now = now();
+    self
+      .keys
+      .iter()
+      .filter(|entry| entry.is_active(now))
+      .max_by_key(|entry| entry.not_before.unwrap_or(0))
+      .ok_or_else(|| {
+        JwtError::InvalidKey("The key ring has no active key".to_string())
+      })
+  }
+
+  /// Firma los claims con la clave activa.
+  pub fn sign(&self, claims: JWTClaims<Value>) -> Result<String, JwtError> {
+    self.active_signing_key()?.sign(claims)
+  }
+
+  /// Firma los claims con la clave activa y una cabecera JOSE propia.
+  pub fn sign_with_header(
+    &self,
+    claims: JWTClaims<Value>,
+    header: TokenHeader,
+  ) -> Result<String, JwtError> {
+    self.active_signing_key()?.sign_with_header(claims, header)
+  }
+
+  /// Busca la clave del `kid` del token. Las claves retiradas solo se
+  /// aceptan hasta que termina el periodo de gracia.
+  pub fn verifying_key(&self, token: &str) -> Result<&VerifyingKey, JwtError> {
+    let header = TokenHeader::decode(token)?;
+    let kid = header.kid.ok_or(JwtError::KeyIdMismatch)?;
+    let entry = self
+      .keys
+      .iter()
+      .find(|entry| entry.kid == kid)
+      .ok_or(JwtError::KeyIdMismatch)?;
+    if let Some(not_after) = entry.not_after {
+      if now() >= not_after.saturating_add(self.grace_period / 1000) {
+        return Err(JwtError::KeyRetired { retired_at: Some(not_after) });
+      }
+    }
+    Ok(&entry.verifying_key)
+  }
+
+  /// Verifica la firma con la clave del `kid` y valida los claims.
+  pub fn verify_with(
+    &self,
+    token: &str,
+    options: &VerifyOptions,
+  ) -> Result<JWTClaims<Value>, JwtError> {
+    self.verifying_key(token)?.verify_with(token, options)
+  }
+
+  /// Igual que `verify_with`, pero devuelve también la cabecera y los claims
+  /// registrados.
+  pub fn verify_full(
+    &self,
+    token: &str,
+    options: &VerifyOptions,
+  ) -> Result<VerifiedToken, JwtError> {
+    self.verifying_key(token)?.verify_full(token, options)
+  }
+
+  fn active_signing_key(&self) -> Result<&SigningKey, JwtError> {
+    self
+      .active_key()?
+      .signing_key
+      .as_ref()
+      .ok_or_else(|| JwtError::Internal("Active key cannot sign".to_string()))
+  }
+
+  fn insert(&mut self, entry: KeyRingEntry) -> Result<(), JwtError> {
+    if self.keys.iter().any(|key| key.kid == entry.kid) {
+      return Err(JwtError::InvalidKey(format!(
+        "Duplicate key ID in the key ring: {}",
+        entry.kid
+      )));
+    }
+    self.keys.push(entry);
+    Ok(())
+  }
+}
+
+// Instante actual en segundos desde la época Unix
+fn now() -> u64 {
+  Clock::now_since_epoch().as_secs()
+}
@@ -0,0 +1,774 @@
+use crate::algorithm::Algorithm;
+use crate::error::JwtError;
+use crate::jwk::Jwk;
+use crate::jws;
+use crate::secret::Secret;
+use crate::token::{TokenHeader, VerifiedToken};
+use crate::validation::VerifyOptions;
+use jwt_simple::prelude::*;
+use jwt_simple::{Error, JWTError};
+use serde_json::Value;
+
+const DEFAULT_RSA_MODULUS_BITS: usize = 2048;
+const EMPTY_SECRET_MESSAGE: &str = "Secret key cannot be empty";
+const KEY_AS_SECRET_MESSAGE: &str =
+  "Public or private keys cannot be used as HMAC secrets";
+
+/// 📌 Material de una clave tal y como llega del exterior
+///
+/// - `Pem` - Clave en formato PEM (PKCS#1, PKCS#8, SPKI o SEC1).
+/// - `Der` - Los mismos formatos codificados en DER. Para curvas elípticas
+///   también se aceptan el escalar privado en bruto y los puntos públicos
+///   comprimidos o sin comprimir; para Ed25519, la semilla de 32 bytes y la
+///   clave pública en bruto.
+/// - `Jwk` - Una clave en formato JWK de cualquier tipo (`oct`, `RSA`, `EC`
+///   u `OKP`).
+#[derive(Debug, Clone)]
+pub enum KeyMaterial {
+  Pem(Secret),
+  Der(Secret<Vec<u8>>),
+  Jwk(Box<Jwk>),
+}
+impl KeyMaterial {
+  // Los secretos HMAC solo pueden llegar como JWK `oct`
+  fn load_secret(
+    &self,
+    algorithm: Algorithm,
+  ) -> Result<Secret<Vec<u8>>, Error> {
+    match self {
+      Self::Jwk(jwk) => {
+        let secret = jwk.private_member("k", &jwk.k)?;
+        check_secret_length(algorithm, secret.expose()).map_err(Error::msg)?;
+        if is_key_material(secret.expose()) {
+          return Err(Error::msg(KEY_AS_SECRET_MESSAGE));
+        }
+        Ok(secret)
+      }
+      _ => Err(Error::msg(requires_secret_message(algorithm))),
+    }
+  }
+
+  // Las claves privadas RSA pueden venir en PKCS#1, en PKCS#8 o como JWK
+  pub(crate) fn load_rsa_private<K>(
+    &self,
+    from_pem: impl Fn(&str) -> Result<K, Error>,
+    from_der: impl Fn(&[u8]) -> Result<K, Error>,
+  ) -> Result<K, Error> {
+    match self {
+      Self::Pem(pem) => from_pem(pem.expose()),
+      Self::Der(der) => from_der(der.expose()),
+      Self::Jwk(jwk) => from_der(jwk.rsa_private_der()?.expose()),
+    }
+  }
+
+  // Las claves públicas RSA pueden venir en PKCS#1, en SPKI o como JWK
+  pub(crate) fn load_rsa_public<K>(
+    &self,
+    from_pem: impl Fn(&str) -> Result<K, Error>,
+    from_der: impl Fn(&[u8]) -> Result<K, Error>,
+    from_components: impl Fn(&[u8], &[u8]) -> Result<K, Error>,
+  ) -> Result<K, Error> {
+    match self {
+      Self::Pem(pem) => from_pem(pem.expose()),
+      Self::Der(der) => from_der(der.expose()),
+      Self::Jwk(jwk) => {
+        from_components(&jwk.member("n", &jwk.n)?, &jwk.member("e", &jwk.e)?)
+      }
+    }
+  }
+
+  // Las claves privadas EC pueden venir en PKCS#8, en SEC1, como escalar o
+  // como JWK
+  pub(crate) fn load_ec_private<K>(
+    &self,
+    from_pem: impl Fn(&str) -> Result<K, Error>,
+    from_der: impl Fn(&[u8]) -> Result<K, Error>,
+    from_bytes: impl Fn(&[u8]) -> Result<K, Error>,
+  ) -> Result<K, Error> {
+    match self {
+      Self::Pem(pem) => from_pem(pem.expose()).or_else(|_| {
+        let (_, der) = sec1::pem::decode_vec(pem.expose().trim().as_bytes())
+          .map_err(|_| JWTError::InvalidKeyPair)?;
+        from_bytes(&sec1_private_scalar(&der)?)
+      }),
+      Self::Der(der) => from_der(der.expose())
+        .or_else(|_| from_bytes(&sec1_private_scalar(der.expose())?))
+        .or_else(|_| from_bytes(der.expose())),
+      Self::Jwk(jwk) => from_bytes(jwk.private_member("d", &jwk.d)?.expose()),
+    }
+  }
+
+  // Las claves públicas EC pueden venir en SPKI, como punto SEC1 o como JWK
+  pub(crate) fn load_ec_public<K>(
+    &self,
+    from_pem: impl Fn(&str) -> Result<K, Error>,
+    from_der: impl Fn(&[u8]) -> Result<K, Error>,
+    from_bytes: impl Fn(&[u8]) -> Result<K, Error>,
+  ) -> Result<K, Error> {
+    match self {
+      Self::Pem(pem) => from_pem(pem.expose()),
+      Self::Der(der) => {
+        from_der(der.expose()).or_else(|_| from_bytes(der.expose()))
+      }
+      Self::Jwk(jwk) => from_bytes(&jwk.ec_point()?),
+    }
+  }
+
+  // Las claves Ed25519 privadas pueden ser PKCS#8, una semilla o un JWK
+  fn load_ed25519_private(&self) -> Result<Ed25519KeyPair, Error> {
+    match self {
+      Self::Pem(pem) => Ed25519KeyPair::from_pem(pem.expose()),
+      Self::Der(der) if der.expose().len() == ed25519_compact::Seed::BYTES => {
+        ed25519_key_pair_from_seed(der.expose())
+      }
+      Self::Der(der) => Ed25519KeyPair::from_der(der.expose())
+        .or_else(|_| Ed25519KeyPair::from_bytes(der.expose())),
+      Self::Jwk(jwk) => {
+        ed25519_key_pair_from_seed(jwk.private_member("d", &jwk.d)?.expose())
+      }
+    }
+  }
+
+  // Las claves Ed25519 públicas pueden ser SPKI, la clave en bruto o un JWK
+  fn load_ed25519_public(&self) -> Result<Ed25519PublicKey, Error> {
+    match self {
+      Self::Pem(pem) => Ed25519PublicKey::from_pem(pem.expose()),
+      Self::Der(der) => Ed25519PublicKey::from_der(der.expose())
+        .or_else(|_| Ed25519PublicKey::from_bytes(der.expose())),
+      Self::Jwk(jwk) => Ed25519PublicKey::from_bytes(&jwk.member("x", &jwk.x)?),
+    }
+  }
+
+  // Un JWK solo se importa con un algoritmo compatible con su tipo y su `alg`
+  fn check_algorithm(&self, algorithm: Algorithm) -> Result<(), Error> {
+    match self {
+      Self::Jwk(jwk) => jwk.check(algorithm),
+      _ => Ok(()),
+    }
+  }
+}
+
+/// 📌 Clave capaz de firmar tokens con el algoritmo indicado
+pub enum SigningKey {
+  HS256(HS256Key),
+  HS384(HS384Key),
+  HS512(HS512Key),
+  RS256(RS256KeyPair),
+  RS384(RS384KeyPair),
+  RS512(RS512KeyPair),
+  PS256(PS256KeyPair),
+  PS384(PS384KeyPair),
+  PS512(PS512KeyPair),
+  ES256(ES256KeyPair),
+  ES384(ES384KeyPair),
+  ES256K(ES256kKeyPair),
+  EdDSA(Ed25519KeyPair),
+}
+impl SigningKey {
+  /// Construye la clave HMAC correspondiente al algoritmo a partir del secreto.
+  ///
+  /// El secreto debe tener al menos tantos bytes como el hash del algoritmo:
+  /// 32 para `HS256`, 48 para `HS384` y 64 para `HS512` (RFC 7518, 3.2).
+  pub fn from_secret(
+    algorithm: Algorithm,
+    secret: &[u8],
+  ) -> Result<Self, JwtError> {
+    check_secret_length(algorithm, secret).map_err(JwtError::InvalidKey)?;
+    Self::from_weak_secret(algorithm, secret)
+  }
+
+  /// Igual que `from_secret`, pero acepta cualquier secreto no vacío. Solo
+  /// debe usarse en pruebas.
+  pub fn from_weak_secret(
+    algorithm: Algorithm,
+    secret: &[u8],
+  ) -> Result<Self, JwtError> {
+    if secret.is_empty() {
+      return Err(JwtError::InvalidKey(EMPTY_SECRET_MESSAGE.to_string()));
+    }
+    if is_key_material(secret) {
+      return Err(JwtError::InvalidKey(KEY_AS_SECRET_MESSAGE.to_string()));
+    }
+    match algorithm {
+      Algorithm::HS256 => Ok(Self::HS256(HS256Key::from_bytes(secret))),
+      Algorithm::HS384 => Ok(Self::HS384(HS384Key::from_bytes(secret))),
+      Algorithm::HS512 => Ok(Self::HS512(HS512Key::from_bytes(secret))),
+      _ => Err(JwtError::InvalidKey(requires_key_message(algorithm))),
+    }
+  }
+
+  /// Importa la clave privada correspondiente al algoritmo desde PEM, DER o
+  /// JWK.
+  ///
+  /// La parte pública de un JWK privado debe corresponder a su clave privada.
+  pub fn from_material(
+    algorithm: Algorithm,
+    material: &KeyMaterial,
+  ) -> Result<Self, JwtError> {
+    let key = Self::import(algorithm, material)
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))?;
+    if let KeyMaterial::Jwk(jwk) = material {
+      let matches = key
+        .to_jwk()?
+        .same_public_key(jwk)
+        .map_err(|err| JwtError::InvalidKey(err.to_string()))?;
+      if !matches {
+        return Err(JwtError::InvalidKey(JWTError::InvalidKeyPair.to_string()));
+      }
+    }
+    Ok(key)
+  }
+
+  fn import(
+    algorithm: Algorithm,
+    material: &KeyMaterial,
+  ) -> Result<Self, Error> {
+    material.check_algorithm(algorithm)?;
+    match algorithm {
+      Algorithm::HS256 => Ok(Self::HS256(HS256Key::from_bytes(
+        material.load_secret(algorithm)?.expose(),
+      ))),
+      Algorithm::HS384 => Ok(Self::HS384(HS384Key::from_bytes(
+        material.load_secret(algorithm)?.expose(),
+      ))),
+      Algorithm::HS512 => Ok(Self::HS512(HS512Key::from_bytes(
+        material.load_secret(algorithm)?.expose(),
+      ))),
+      Algorithm::RS256 => Ok(Self::RS256(
+        material
+          .load_rsa_private(RS256KeyPair::from_pem, RS256KeyPair::from_der)?,
+      )),
+      Algorithm::RS384 => Ok(Self::RS384(
+        material
+          .load_rsa_private(RS384KeyPair::from_pem, RS384KeyPair::from_der)?,
+      )),
+      Algorithm::RS512 => Ok(Self::RS512(
+        material
+          .load_rsa_private(RS512KeyPair::from_pem, RS512KeyPair::from_der)?,
+      )),
+      Algorithm::PS256 => Ok(Self::PS256(
+        material
+          .load_rsa_private(PS256KeyPair::from_pem, PS256KeyPair::from_der)?,
+      )),
+      Algorithm::PS384 => Ok(Self::PS384(
+        material
+          .load_rsa_private(PS384KeyPair::from_pem, PS384KeyPair::from_der)?,
+      )),
+      Algorithm::PS512 => Ok(Self::PS512(
+        material
+          .load_rsa_private(PS512KeyPair::from_pem, PS512KeyPair::from_der)?,
+      )),
+      Algorithm::ES256 => Ok(Self::ES256(material.load_ec_private(
+        ES256KeyPair::from_pem,
+        ES256KeyPair::from_der,
+        checked_scalar(ES256KeyPair::from_bytes, 32),
+      )?)),
+      Algorithm::ES384 => Ok(Self::ES384(material.load_ec_private(
+        ES384KeyPair::from_pem,
+        ES384KeyPair::from_der,
+        checked_scalar(ES384KeyPair::from_bytes, 48),
+      )?)),
+      Algorithm::ES256K => Ok(Self::ES256K(material.load_ec_private(
+        ES256kKeyPair::from_pem,
+        ES256kKeyPair::from_der,
+        checked_scalar(ES256kKeyPair::from_bytes, 32),
+      )?)),
+      Algorithm::EdDSA => Ok(Self::EdDSA(material.load_ed25519_private()?)),
+    }
+  }
+
+  /// Genera un par de claves nuevo para el algoritmo asimétrico indicado.
+  ///
+  /// `modulus_bits` solo se usa con RSA y puede ser 2048 (por defecto), 3072
+  /// o 4096. Los secretos HMAC se generan con `generate_secret`.
+  pub fn generate(
+    algorithm: Algorithm,
+    modulus_bits: Option<usize>,
+  ) -> Result<Self, JwtError> {
+    let modulus_bits = modulus_bits.unwrap_or(DEFAULT_RSA_MODULUS_BITS);
+    let key = match algorithm {
+      Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+        return Err(JwtError::InvalidKey(requires_secret_message(algorithm)))
+      }
+      Algorithm::RS256 => RS256KeyPair::generate(modulus_bits).map(Self::RS256),
+      Algorithm::RS384 => RS384KeyPair::generate(modulus_bits).map(Self::RS384),
+      Algorithm::RS512 => RS512KeyPair::generate(modulus_bits).map(Self::RS512),
+      Algorithm::PS256 => PS256KeyPair::generate(modulus_bits).map(Self::PS256),
+      Algorithm::PS384 => PS384KeyPair::generate(modulus_bits).map(Self::PS384),
+      Algorithm::PS512 => PS512KeyPair::generate(modulus_bits).map(Self::PS512),
+      Algorithm::ES256 => Ok(Self::ES256(ES256KeyPair::generate())),
+      Algorithm::ES384 => Ok(Self::ES384(ES384KeyPair::generate())),
+      Algorithm::ES256K => Ok(Self::ES256K(ES256kKeyPair::generate())),
+      Algorithm::EdDSA => Ok(Self::EdDSA(Ed25519KeyPair::generate())),
+    };
+    key.map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  /// Construye la clave Ed25519 a partir de una semilla de 32 bytes.
+  pub fn from_ed25519_seed(seed: &[u8]) -> Result<Self, JwtError> {
+    ed25519_key_pair_from_seed(seed)
+      .map(Self::EdDSA)
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  pub fn algorithm(&self) -> Algorithm {
+    match self {
+      Self::HS256(_) => Algorithm::HS256,
+      Self::HS384(_) => Algorithm::HS384,
+      Self::HS512(_) => Algorithm::HS512,
+      Self::RS256(_) => Algorithm::RS256,
+      Self::RS384(_) => Algorithm::RS384,
+      Self::RS512(_) => Algorithm::RS512,
+      Self::PS256(_) => Algorithm::PS256,
+      Self::PS384(_) => Algorithm::PS384,
+      Self::PS512(_) => Algorithm::PS512,
+      Self::ES256(_) => Algorithm::ES256,
+      Self::ES384(_) => Algorithm::ES384,
+      Self::ES256K(_) => Algorithm::ES256K,
+      Self::EdDSA(_) => Algorithm::EdDSA,
+    }
+  }
+
+  /// Asocia un identificador a la clave, que se incluye como `kid` en la
+  /// cabecera de los tokens que firma.
+  pub fn with_key_id(self, kid: &str) -> Self {
+    match self {
+      Self::HS256(key) => Self::HS256(key.with_key_id(kid)),
+      Self::HS384(key) => Self::HS384(key.with_key_id(kid)),
+      Self::HS512(key) => Self::HS512(key.with_key_id(kid)),
+      Self::RS256(key) => Self::RS256(key.with_key_id(kid)),
+      Self::RS384(key) => Self::RS384(key.with_key_id(kid)),
+      Self::RS512(key) => Self::RS512(key.with_key_id(kid)),
+      Self::PS256(key) => Self::PS256(key.with_key_id(kid)),
+      Self::PS384(key) => Self::PS384(key.with_key_id(kid)),
+      Self::PS512(key) => Self::PS512(key.with_key_id(kid)),
+      Self::ES256(key) => Self::ES256(key.with_key_id(kid)),
+      Self::ES384(key) => Self::ES384(key.with_key_id(kid)),
+      Self::ES256K(key) => Self::ES256K(key.with_key_id(kid)),
+      Self::EdDSA(key) => Self::EdDSA(key.with_key_id(kid)),
+    }
+  }
+
+  /// Devuelve la clave con la que se verifican los tokens que firma.
+  pub fn verifying_key(&self) -> VerifyingKey {
+    match self {
+      Self::HS256(key) => VerifyingKey::HS256(key.clone()),
+      Self::HS384(key) => VerifyingKey::HS384(key.clone()),
+      Self::HS512(key) => VerifyingKey::HS512(key.clone()),
+      Self::RS256(key) => VerifyingKey::RS256(key.public_key()),
+      Self::RS384(key) => VerifyingKey::RS384(key.public_key()),
+      Self::RS512(key) => VerifyingKey::RS512(key.public_key()),
+      Self::PS256(key) => VerifyingKey::PS256(key.public_key()),
+      Self::PS384(key) => VerifyingKey::PS384(key.public_key()),
+      Self::PS512(key) => VerifyingKey::PS512(key.public_key()),
+      Self::ES256(key) => VerifyingKey::ES256(key.public_key()),
+      Self::ES384(key) => VerifyingKey::ES384(key.public_key()),
+      Self::ES256K(key) => VerifyingKey::ES256K(key.public_key()),
+      Self::EdDSA(key) => VerifyingKey::EdDSA(key.public_key()),
+    }
+  }
+
+  /// Exporta la clave privada en PEM (PKCS#8). Los secretos HMAC no tienen
+  /// representación PEM.
+  pub fn to_pem(&self) -> Result<String, JwtError> {
+    let pem = match self {
+      Self::HS256(_) | Self::HS384(_) | Self::HS512(_) => {
+        return Err(no_pem_error());
+      }
+      Self::RS256(key) => key.to_pem(),
+      Self::RS384(key) => key.to_pem(),
+      Self::RS512(key) => key.to_pem(),
+      Self::PS256(key) => key.to_pem(),
+      Self::PS384(key) => key.to_pem(),
+      Self::PS512(key) => key.to_pem(),
+      Self::ES256(key) => key.to_pem(),
+      Self::ES384(key) => key.to_pem(),
+      Self::ES256K(key) => key.to_pem(),
+      Self::EdDSA(key) => Ok(key.to_pem()),
+    };
+    pem.map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  /// Exporta la clave completa, incluidos los miembros privados, como JWK.
+  /// Las copias intermedias de los bytes privados se borran al terminar.
+  pub fn to_jwk(&self) -> Result<Jwk, JwtError> {
+    let jwk = match self {
+      Self::HS256(key) => Jwk::oct(Secret::new(key.to_bytes()).expose()),
+      Self::HS384(key) => Jwk::oct(Secret::new(key.to_bytes()).expose()),
+      Self::HS512(key) => Jwk::oct(Secret::new(key.to_bytes()).expose()),
+      Self::RS256(key) => Jwk::rsa_private(key.key_pair().as_ref()),
+      Self::RS384(key) => Jwk::rsa_private(key.key_pair().as_ref()),
+      Self::RS512(key) => Jwk::rsa_private(key.key_pair().as_ref()),
+      Self::PS256(key) => Jwk::rsa_private(key.key_pair().as_ref()),
+      Self::PS384(key) => Jwk::rsa_private(key.key_pair().as_ref()),
+      Self::PS512(key) => Jwk::rsa_private(key.key_pair().as_ref()),
+      Self::ES256(key) => Jwk::ec(
+        Algorithm::ES256,
+        &key.key_pair().public_key().to_bytes_uncompressed(),
+        Some(Secret::new(key.to_bytes()).expose()),
+      ),
+      Self::ES384(key) => Jwk::ec(
+        Algorithm::ES384,
+        &key.key_pair().public_key().to_bytes_uncompressed(),
+        Some(Secret::new(key.to_bytes()).expose()),
+      ),
+      Self::ES256K(key) => Jwk::ec(
+        Algorithm::ES256K,
+        &key.key_pair().public_key().to_bytes_uncompressed(),
+        Some(Secret::new(key.to_bytes()).expose()),
+      ),
+      Self::EdDSA(key) => {
+        let key_pair = Secret::new(key.to_bytes());
+        let seed = &key_pair.expose()[..ed25519_compact::Seed::BYTES];
+        Jwk::okp(&key.public_key().to_bytes(), Some(seed))
+      }
+    };
+    jwk
+      .map(|jwk| jwk.bound_to(self.algorithm()))
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  /// Firma los claims con la cabecera por defecto (`typ: JWT`).
+  pub fn sign(&self, claims: JWTClaims<Value>) -> Result<String, JwtError> {
+    let header =
+      TokenHeader { typ: Some("JWT".to_string()), ..Default::default() };
+    self.sign_with_header(claims, header)
+  }
+
+  /// Firma los claims con una cabecera JOSE propia. `alg` lo fija siempre la
+  /// clave, igual que `kid` si la clave tiene uno.
+  pub fn sign_with_header(
+    &self,
+    claims: JWTClaims<Value>,
+    header: TokenHeader,
+  ) -> Result<String, JwtError> {
+    jws::encode(self, header, &claims)
+  }
+
+  /// Firma un payload arbitrario sin codificarlo (`b64: false`, RFC 7797)
+  /// y lo deja fuera del token, que queda como `cabecera..firma`. El
+  /// payload viaja aparte, por ejemplo como cuerpo de un webhook.
+  pub fn sign_detached(
+    &self,
+    payload: &[u8],
+    header: TokenHeader,
+  ) -> Result<String, JwtError> {
+    jws::encode_detached(self, header, payload)
+  }
+}
+
+/// 📌 Clave capaz de verificar tokens con el algoritmo indicado
+pub enum VerifyingKey {
+  HS256(HS256Key),
+  HS384(HS384Key),
+  HS512(HS512Key),
+  RS256(RS256PublicKey),
+  RS384(RS384PublicKey),
+  RS512(RS512PublicKey),
+  PS256(PS256PublicKey),
+  PS384(PS384PublicKey),
+  PS512(PS512PublicKey),
+  ES256(ES256PublicKey),
+  ES384(ES384PublicKey),
+  ES256K(ES256kPublicKey),
+  EdDSA(Ed25519PublicKey),
+}
+impl VerifyingKey {
+  /// Construye la clave HMAC correspondiente al algoritmo a partir del secreto.
+  ///
+  /// El secreto debe tener al menos tantos bytes como el hash del algoritmo:
+  /// 32 para `HS256`, 48 para `HS384` y 64 para `HS512` (RFC 7518, 3.2).
+  pub fn from_secret(
+    algorithm: Algorithm,
+    secret: &[u8],
+  ) -> Result<Self, JwtError> {
+    check_secret_length(algorithm, secret).map_err(JwtError::InvalidKey)?;
+    Self::from_weak_secret(algorithm, secret)
+  }
+
+  /// Igual que `from_secret`, pero acepta cualquier secreto no vacío. Solo
+  /// debe usarse en pruebas.
+  pub fn from_weak_secret(
+    algorithm: Algorithm,
+    secret: &[u8],
+  ) -> Result<Self, JwtError> {
+    if secret.is_empty() {
+      return Err(JwtError::InvalidKey(EMPTY_SECRET_MESSAGE.to_string()));
+    }
+    if is_key_material(secret) {
+      return Err(JwtError::InvalidKey(KEY_AS_SECRET_MESSAGE.to_string()));
+    }
+    match algorithm {
+      Algorithm::HS256 => Ok(Self::HS256(HS256Key::from_bytes(secret))),
+      Algorithm::HS384 => Ok(Self::HS384(HS384Key::from_bytes(secret))),
+      Algorithm::HS512 => Ok(Self::HS512(HS512Key::from_bytes(secret))),
+      _ => Err(JwtError::InvalidKey(requires_key_message(algorithm))),
+    }
+  }
+
+  /// Importa la clave pública correspondiente al algoritmo desde PEM, DER o
+  /// JWK.
+  pub fn from_material(
+    algorithm: Algorithm,
+    material: &KeyMaterial,
+  ) -> Result<Self, JwtError> {
+    Self::import(algorithm, material)
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  fn import(
+    algorithm: Algorithm,
+    material: &KeyMaterial,
+  ) -> Result<Self, Error> {
+    material.check_algorithm(algorithm)?;
+    match algorithm {
+      Algorithm::HS256 => Ok(Self::HS256(HS256Key::from_bytes(
+        material.load_secret(algorithm)?.expose(),
+      ))),
+      Algorithm::HS384 => Ok(Self::HS384(HS384Key::from_bytes(
+        material.load_secret(algorithm)?.expose(),
+      ))),
+      Algorithm::HS512 => Ok(Self::HS512(HS512Key::from_bytes(
+        material.load_secret(algorithm)?.expose(),
+      ))),
+      Algorithm::RS256 => Ok(Self::RS256(material.load_rsa_public(
+        RS256PublicKey::from_pem,
+        RS256PublicKey::from_der,
+        RS256PublicKey::from_components,
+      )?)),
+      Algorithm::RS384 => Ok(Self::RS384(material.load_rsa_public(
+        RS384PublicKey::from_pem,
+        RS384PublicKey::from_der,
+        RS384PublicKey::from_components,
+      )?)),
+      Algorithm::RS512 => Ok(Self::RS512(material.load_rsa_public(
+        RS512PublicKey::from_pem,
+        RS512PublicKey::from_der,
+        RS512PublicKey::from_components,
+      )?)),
+      Algorithm::PS256 => Ok(Self::PS256(material.load_rsa_public(
+        PS256PublicKey::from_pem,
+        PS256PublicKey::from_der,
+        PS256PublicKey::from_components,
+      )?)),
+      Algorithm::PS384 => Ok(Self::PS384(material.load_rsa_public(
+        PS384PublicKey::from_pem,
+        PS384PublicKey::from_der,
+        PS384PublicKey::from_components,
+      )?)),
+      Algorithm::PS512 => Ok(Self::PS512(material.load_rsa_public(
+        PS512PublicKey::from_pem,
+        PS512PublicKey::from_der,
+        PS512PublicKey::from_components,
+      )?)),
+      Algorithm::ES256 => Ok(Self::ES256(material.load_ec_public(
+        ES256PublicKey::from_pem,
+        ES256PublicKey::from_der,
+        ES256PublicKey::from_bytes,
+      )?)),
+      Algorithm::ES384 => Ok(Self::ES384(material.load_ec_public(
+        ES384PublicKey::from_pem,
+        ES384PublicKey::from_der,
+        ES384PublicKey::from_bytes,
+      )?)),
+      Algorithm::ES256K => Ok(Self::ES256K(material.load_ec_public(
+        ES256kPublicKey::from_pem,
+        ES256kPublicKey::from_der,
+        ES256kPublicKey::from_bytes,
+      )?)),
+      Algorithm::EdDSA => Ok(Self::EdDSA(material.load_ed25519_public()?)),
+    }
+  }
+
+  pub fn algorithm(&self) -> Algorithm {
+    match self {
+      Self::HS256(_) => Algorithm::HS256,
+      Self::HS384(_) => Algorithm::HS384,
+      Self::HS512(_) => Algorithm::HS512,
+      Self::RS256(_) => Algorithm::RS256,
+      Self::RS384(_) => Algorithm::RS384,
+      Self::RS512(_) => Algorithm::RS512,
+      Self::PS256(_) => Algorithm::PS256,
+      Self::PS384(_) => Algorithm::PS384,
+      Self::PS512(_) => Algorithm::PS512,
+      Self::ES256(_) => Algorithm::ES256,
+      Self::ES384(_) => Algorithm::ES384,
+      Self::ES256K(_) => Algorithm::ES256K,
+      Self::EdDSA(_) => Algorithm::EdDSA,
+    }
+  }
+
+  /// Exporta la clave pública en PEM (SPKI). Los secretos HMAC no tienen
+  /// representación PEM.
+  pub fn to_pem(&self) -> Result<String, JwtError> {
+    let pem = match self {
+      Self::HS256(_) | Self::HS384(_) | Self::HS512(_) => {
+        return Err(no_pem_error());
+      }
+      Self::RS256(key) => key.to_pem(),
+      Self::RS384(key) => key.to_pem(),
+      Self::RS512(key) => key.to_pem(),
+      Self::PS256(key) => key.to_pem(),
+      Self::PS384(key) => key.to_pem(),
+      Self::PS512(key) => key.to_pem(),
+      Self::ES256(key) => key.to_pem(),
+      Self::ES384(key) => key.to_pem(),
+      Self::ES256K(key) => key.to_pem(),
+      Self::EdDSA(key) => Ok(key.to_pem()),
+    };
+    pem.map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  /// Exporta la clave como JWK. Salvo los secretos HMAC, que se exportan como
+  /// `oct`, el resultado solo contiene la parte pública.
+  pub fn to_jwk(&self) -> Result<Jwk, JwtError> {
+    let jwk = match self {
+      Self::HS256(key) => Jwk::oct(&key.to_bytes()),
+      Self::HS384(key) => Jwk::oct(&key.to_bytes()),
+      Self::HS512(key) => Jwk::oct(&key.to_bytes()),
+      Self::RS256(key) => rsa_public_jwk(key.to_components()),
+      Self::RS384(key) => rsa_public_jwk(key.to_components()),
+      Self::RS512(key) => rsa_public_jwk(key.to_components()),
+      Self::PS256(key) => rsa_public_jwk(key.to_components()),
+      Self::PS384(key) => rsa_public_jwk(key.to_components()),
+      Self::PS512(key) => rsa_public_jwk(key.to_components()),
+      Self::ES256(key) => Jwk::ec(
+        Algorithm::ES256,
+        &key.public_key().to_bytes_uncompressed(),
+        None,
+      ),
+      Self::ES384(key) => Jwk::ec(
+        Algorithm::ES384,
+        &key.public_key().to_bytes_uncompressed(),
+        None,
+      ),
+      Self::ES256K(key) => Jwk::ec(
+        Algorithm::ES256K,
+        &key.public_key().to_bytes_uncompressed(),
+        None,
+      ),
+      Self::EdDSA(key) => Jwk::okp(&key.to_bytes(), None),
+    };
+    jwk
+      .map(|jwk| jwk.bound_to(self.algorithm()))
+      .map_err(|err| JwtError::InvalidKey(err.to_string()))
+  }
+
+  /// Verifica la firma y valida los claims según las opciones indicadas.
+  pub fn verify_with(
+    &self,
+    token: &str,
+    options: &VerifyOptions,
+  ) -> Result<JWTClaims<Value>, JwtError> {
+    options.check_algorithm(self.algorithm())?;
+    let (header, claims) = jws::decode(self, token)?;
+    options.check_header(&header)?;
+    options.validate(&claims)?;
+    Ok(claims)
+  }
+
+  /// Igual que `verify_with`, pero devuelve también la cabecera y los claims
+  /// registrados.
+  pub fn verify_full(
+    &self,
+    token: &str,
+    options: &VerifyOptions,
+  ) -> Result<VerifiedToken, JwtError> {
+    VerifiedToken::new(token, self.verify_with(token, options)?)
+  }
+
+  /// Verifica un JWS con el payload separado (`cabecera..firma`) y devuelve
+  /// su cabecera. Admite payloads sin codificar (`b64: false`) y
+  /// codificados en base64url. No hay claims que validar, así que de las
+  /// opciones solo se aplican las de la cabecera y el algoritmo.
+  pub fn verify_detached(
+    &self,
+    token: &str,
+    payload: &[u8],
+    options: &VerifyOptions,
+  ) -> Result<TokenHeader, JwtError> {
+    options.check_algorithm(self.algorithm())?;
+    let header = jws::decode_detached(self, token, payload)?;
+    options.check_detached_header(&header)?;
+    Ok(header)
+  }
+}
+
+/// 📌 Lee el algoritmo declarado en la cabecera del token sin verificarlo
+pub fn token_algorithm(token: &str) -> Result<Algorithm, JwtError> {
+  TokenHeader::decode(token)?.alg.parse()
+}
+
+// Extrae el escalar privado de una clave SEC1 (`EC PRIVATE KEY`)
+fn sec1_private_scalar(der: &[u8]) -> Result<Vec<u8>, Error> {
+  let key =
+    sec1::EcPrivateKey::try_from(der).map_err(|_| JWTError::InvalidKeyPair)?;
+  Ok(key.private_key.to_vec())
+}
+
+// `jwt-simple` entra en pánico si el escalar privado no tiene el tamaño de la
+// curva, así que la longitud se comprueba antes de importarlo
+fn checked_scalar<K>(
+  from_bytes: impl Fn(&[u8]) -> Result<K, Error>,
+  size: usize,
+) -> impl Fn(&[u8]) -> Result<K, Error> {
+  move |scalar| {
+    if scalar.len() != size {
+      return Err(JWTError::InvalidKeyPair.into());
+    }
+    from_bytes(scalar)
+  }
+}
+
+fn rsa_public_jwk(components: RSAPublicKeyComponents) -> Result<Jwk, Error> {
+  Jwk::rsa(&components.n, &components.e)
+}
+
+fn ed25519_key_pair_from_seed(seed: &[u8]) -> Result<Ed25519KeyPair, Error> {
+  let seed = ed25519_compact::Seed::from_slice(seed)?;
+  let key_pair = ed25519_compact::KeyPair::from_seed(seed);
+  Ed25519KeyPair::from_bytes(key_pair.as_ref())
+}
+
+// Política de longitud mínima de los secretos HMAC
+fn check_secret_length(
+  algorithm: Algorithm,
+  secret: &[u8],
+) -> Result<(), String> {
+  if secret.is_empty() {
+    return Err(EMPTY_SECRET_MESSAGE.to_string());
+  }
+  if algorithm.is_hmac() && secret.len() < algorithm.hash_len() {
+    return Err(format!(
+      "Secret for {algorithm} must be at least {} bytes long",
+      algorithm.hash_len()
+    ));
+  }
+  Ok(())
+}
+
+// Una clave pública (o privada) nunca sirve como secreto HMAC; si no, quien
+// la conozca podría firmar tokens `HS256` usándola como secreto
+fn is_key_material(secret: &[u8]) -> bool {
+  let text = String::from_utf8_lossy(secret);
+  let text = text.trim_start();
+  text.starts_with("-----BEGIN")
+    || serde_json::from_str::<Jwk>(text).is_ok()
+    || rsa::pkcs8::spki::SubjectPublicKeyInfo::try_from(secret).is_ok()
+    || rsa::pkcs8::PrivateKeyInfo::try_from(secret).is_ok()
+    || rsa::pkcs1::RsaPublicKey::try_from(secret).is_ok()
+    || rsa::pkcs1::RsaPrivateKey::try_from(secret).is_ok()
+    || sec1::EcPrivateKey::try_from(secret).is_ok()
+}
+
+fn no_pem_error() -> JwtError {
+  JwtError::InvalidKey("HMAC secrets have no PEM encoding".to_string())
+}
+
+fn requires_key_message(algorithm: Algorithm) -> String {
+  format!("Algorithm {algorithm} requires a PEM or DER key")
+}
+
+fn requires_secret_message(algorithm: Algorithm) -> String {
+  format!("Algorithm {algorithm} requires a secret")
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use zeroize::Zeroize;
+
+/// 📌 Material secreto: secretos HMAC, claves privadas y sus miembros JWK
+///
+/// - `Debug` y `Display` muestran `[REDACTED]` en lugar del valor.
+/// - No implementa `Serialize`: los campos que deban exportar el valor lo
+///   piden de forma explícita con `serialize_with`.
+/// - El valor se sobrescribe con ceros al liberarse, también cuando JS libera
+///   el objeto que lo contiene (`free()` de wasm-bindgen).
+#[derive(Default)]
+pub struct Secret<T: Zeroize = String>(T);
+impl<T: Zeroize> Secret<T> {
+  pub fn new(value: T) -> Self {
+    Self(value)
+  }
+
+  /// Da acceso al valor secreto.
+  pub fn expose(&self) -> &T {
+    &self.0
+  }
+}
+impl<T: Zeroize> Drop for Secret<T> {
+  fn drop(&mut self) {
+    self.0.zeroize();
+  }
+}
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+  fn clone(&self) -> Self {
+    Self(self.0.clone())
+  }
+}
+impl<T: Zeroize + PartialEq> PartialEq for Secret<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.0 == other.0
+  }
+}
+impl<T: Zeroize + Eq> Eq for Secret<T> {}
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("[REDACTED]")
+  }
+}
+impl<T: Zeroize> fmt::Display for Secret<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("[REDACTED]")
+  }
+}
+impl From<String> for Secret {
+  fn from(value: String) -> Self {
+    Self(value)
+  }
+}
+impl From<&str> for Secret {
+  fn from(value: &str) -> Self {
+    Self(value.to_string())
+  }
+}
+impl From<Vec<u8>> for Secret<Vec<u8>> {
+  fn from(value: Vec<u8>) -> Self {
+    Self(value)
+  }
+}
+impl<'de, T: Zeroize + Deserialize<'de>> Deserialize<'de> for Secret<T> {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    T::deserialize(deserializer).map(Self)
+  }
+}
+
+// Serializa el valor de un secreto opcional; solo para los campos que
+// exportan claves a propósito (JWK privados, secretos generados)
+pub(crate) fn serialize_exposed<S: Serializer, T: Zeroize + Serialize>(
+  secret: &Option<Secret<T>>,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  secret.as_ref().map(Secret::expose).serialize(serializer)
+}
@@ -0,0 +1,169 @@
+use crate::error::JwtError;
+use crate::Audience;
+use jwt_simple::prelude::*;
+use serde_json::{Map, Value};
+
+/// 📌 Cabecera JOSE del token
+///
+/// Además de los parámetros registrados más habituales, conserva en `extra`
+/// cualquier otro parámetro, público o privado, tal y como venga en el token.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TokenHeader {
+  pub alg: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub typ: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub kid: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub cty: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub jku: Option<String>,
+  #[serde(
+    default,
+    rename = "x5t#S256",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub x5t_s256: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub crit: Option<Vec<String>>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+impl TokenHeader {
+  /// Lee la cabecera del token sin verificar la firma.
+  pub fn decode(token: &str) -> Result<Self, JwtError> {
+    let header_b64 = token.split('.').next().unwrap_or_default();
+    if header_b64.len() > MAX_HEADER_LENGTH {
+      return Err(JwtError::Malformed("Token header is too large".to_string()));
+    }
+    decode_part(header_b64)
+  }
+
+  /// Comprueba si el `typ` de la cabecera es el tipo de medio indicado.
+  ///
+  /// Como indica la RFC 7515, la comparación no distingue mayúsculas y el
+  /// prefijo `application/` es opcional: `at+jwt` equivale a
+  /// `application/at+JWT`.
+  pub fn has_type(&self, media_type: &str) -> bool {
+    self.typ.as_deref().is_some_and(|typ| {
+      normalize_media_type(typ) == normalize_media_type(media_type)
+    })
+  }
+}
+
+/// 📌 Claims registrados (RFC 7519)
+///
+/// Las marcas de tiempo (`iat`, `exp`, `nbf`) son segundos desde la época
+/// Unix.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RegisteredClaims {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub iat: Option<u64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub exp: Option<u64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub nbf: Option<u64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub iss: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub sub: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub aud: Option<Audience>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub jti: Option<String>,
+}
+impl<T> From<&JWTClaims<T>> for RegisteredClaims {
+  fn from(claims: &JWTClaims<T>) -> Self {
+    Self {
+      iat: claims.issued_at.map(|time| time.as_secs()),
+      exp: claims.expires_at.map(|time| time.as_secs()),
+      nbf: claims.invalid_before.map(|time| time.as_secs()),
+      iss: claims.issuer.clone(),
+      sub: claims.subject.clone(),
+      aud: claims.audiences.clone().map(|audiences| match audiences {
+        Audiences::AsString(audience) => Audience::One(audience),
+        Audiences::AsSet(audiences) => {
+          Audience::Many(audiences.into_iter().collect())
+        }
+      }),
+      jti: claims.jwt_id.clone(),
+    }
+  }
+}
+
+/// 📌 Resultado completo de una verificación
+///
+/// - `header` - La cabecera JOSE del token.
+/// - `claims` - Los claims registrados.
+/// - `payload` - Los claims personalizados.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifiedToken {
+  pub header: TokenHeader,
+  pub claims: RegisteredClaims,
+  pub payload: Value,
+}
+impl VerifiedToken {
+  /// Reúne la cabecera del token y los claims ya verificados.
+  pub fn new(token: &str, claims: JWTClaims<Value>) -> Result<Self, JwtError> {
+    Ok(Self {
+      header: TokenHeader::decode(token)?,
+      claims: RegisteredClaims::from(&claims),
+      payload: claims.custom,
+    })
+  }
+}
+
+/// 📌 Contenido de un token leído SIN verificar la firma
+///
+/// Sirve para inspeccionar la cabecera (por ejemplo, `kid` o `alg` antes de
+/// elegir la clave) o depurar tokens, pero sus datos no son de fiar: nada
+/// garantiza que el token lo haya emitido quien dice. `verified` siempre es
+/// `false` para que quede claro también al serializarlo.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnverifiedToken {
+  pub verified: bool,
+  pub header: TokenHeader,
+  pub claims: RegisteredClaims,
+  pub payload: Value,
+}
+impl UnverifiedToken {
+  /// Separa y decodifica un token compacto sin comprobar la firma.
+  pub fn decode(token: &str) -> Result<Self, JwtError> {
+    let mut parts = token.split('.');
+    let (Some(header_b64), Some(claims_b64), Some(_), None) =
+      (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+      return Err(JwtError::Malformed(
+        "Token must have three dot-separated parts".to_string(),
+      ));
+    };
+    let claims: JWTClaims<Value> = decode_part(claims_b64)?;
+    Ok(Self {
+      verified: false,
+      header: decode_part(header_b64)?,
+      claims: RegisteredClaims::from(&claims),
+      payload: claims.custom,
+    })
+  }
+}
+
+// Tamaño máximo de la cabecera codificada, el mismo límite que `jwt-simple`
+pub(crate) const MAX_HEADER_LENGTH: usize = 8192;
+
+// Decodifica un segmento base64url con JSON
+pub(crate) fn decode_part<T: serde::de::DeserializeOwned>(
+  part: &str,
+) -> Result<T, JwtError> {
+  let json = Base64UrlSafeNoPadding::decode_to_vec(part, None)
+    .map_err(|err| JwtError::Malformed(err.to_string()))?;
+  serde_json::from_slice(&json)
+    .map_err(|err| JwtError::Malformed(err.to_string()))
+}
+
+fn normalize_media_type(media_type: &str) -> String {
+  let media_type = media_type.to_ascii_lowercase();
+  match media_type.strip_prefix("application/") {
+    Some(media_type) => media_type.to_string(),
+    None => media_type,
+  }
+}
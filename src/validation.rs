@@ -0,0 +1,163 @@
+use crate::algorithm::Algorithm;
+use crate::critical::CriticalHeaders;
+use crate::duration;
+use crate::error::JwtError;
+use crate::jws;
+use crate::token::TokenHeader;
+use jwt_simple::prelude::*;
+use serde_json::Value;
+
+// Tolerancia de reloj por defecto, en milisegundos
+const DEFAULT_LEEWAY: u64 = 15 * 60 * 1000;
+
+/// 📌 Opciones de validación de los claims de un token
+///
+/// Todos los campos son opcionales; los que no se indiquen no se comprueban.
+/// `leeway` y `max_age` aceptan los mismos formatos de duración que
+/// `JwtOptions.expires_in`. `leeway` es la tolerancia de reloj al comprobar
+/// `exp`, `nbf` e `iat`; por defecto son 15 minutos, como en `jwt-simple`.
+/// `allow_weak_secret` acepta secretos HMAC más cortos que el mínimo del
+/// algoritmo y solo debe usarse en pruebas.
+///
+/// `allowed_algorithms` limita los algoritmos aceptados, de modo que el `alg`
+/// de la cabecera nunca decide por sí solo cómo se verifica el token. `none`
+/// no es un algoritmo soportado y se rechaza siempre.
+///
+/// `required_type` exige un `typ` concreto en la cabecera, como `at+jwt` para
+/// los access tokens de la RFC 9068, de modo que un token de otro tipo
+/// firmado con la misma clave no se acepte en su lugar (RFC 8725, 3.11). Si
+/// no se indica, el `typ` no se comprueba.
+///
+/// `critical_headers` registra las extensiones críticas (`crit`) que se
+/// entienden; no se serializa, porque contiene funciones.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct VerifyOptions {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub allowed_issuers: Option<HashSet<String>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub allowed_audiences: Option<HashSet<String>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub required_subject: Option<String>,
+  #[serde(
+    default,
+    deserialize_with = "duration::deserialize_optional_millis",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub leeway: Option<u64>,
+  #[serde(
+    default,
+    deserialize_with = "duration::deserialize_optional_millis",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub max_age: Option<u64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub required_jwt_id: Option<String>,
+  #[serde(default)]
+  pub allow_weak_secret: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub allowed_algorithms: Option<Vec<Algorithm>>,
+  #[serde(
+    default,
+    alias = "required_typ",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub required_type: Option<String>,
+  #[serde(skip)]
+  pub critical_headers: CriticalHeaders,
+}
+impl VerifyOptions {
+  /// Valida los claims de un token cuya firma ya se ha comprobado: las marcas
+  /// de tiempo (`iat`, `nbf`, `exp`) con la tolerancia de reloj y `max_age`,
+  /// y `iss`, `sub`, `aud` y `jti` frente a los valores exigidos.
+  pub fn validate(&self, claims: &JWTClaims<Value>) -> Result<(), JwtError> {
+    let now = Clock::now_since_epoch().as_secs();
+    let leeway = self.leeway.unwrap_or(DEFAULT_LEEWAY) / 1000;
+    let not_before = claims.invalid_before.map(|time| time.as_secs());
+
+    if let Some(issued_at) = claims.issued_at.map(|time| time.as_secs()) {
+      if issued_at > now.saturating_add(leeway) {
+        return Err(JwtError::NotYetValid { not_before });
+      }
+      if let Some(max_age) = self.max_age {
+        if now.saturating_sub(issued_at) > max_age / 1000 {
+          return Err(JwtError::TooOld);
+        }
+      }
+    }
+    if let Some(not_before) = not_before {
+      if now.saturating_add(leeway) < not_before {
+        return Err(JwtError::NotYetValid { not_before: Some(not_before) });
+      }
+    }
+    if let Some(expires_at) = claims.expires_at.map(|time| time.as_secs()) {
+      if now.saturating_sub(leeway) > expires_at {
+        return Err(JwtError::Expired { expired_at: Some(expires_at) });
+      }
+    }
+    if let Some(allowed_issuers) = &self.allowed_issuers {
+      if !claims
+        .issuer
+        .as_ref()
+        .is_some_and(|issuer| allowed_issuers.contains(issuer))
+      {
+        return Err(JwtError::IssuerMismatch);
+      }
+    }
+    if let Some(required_subject) = &self.required_subject {
+      if claims.subject.as_ref() != Some(required_subject) {
+        return Err(JwtError::SubjectMismatch);
+      }
+    }
+    if let Some(allowed_audiences) = &self.allowed_audiences {
+      if !claims
+        .audiences
+        .as_ref()
+        .is_some_and(|audiences| audiences.contains(allowed_audiences))
+      {
+        return Err(JwtError::AudienceMismatch);
+      }
+    }
+    if let Some(required_jwt_id) = &self.required_jwt_id {
+      if claims.jwt_id.as_ref() != Some(required_jwt_id) {
+        return Err(JwtError::JwtIdMismatch);
+      }
+    }
+    Ok(())
+  }
+
+  /// Comprueba que el algoritmo está entre los permitidos, si se indicaron.
+  pub fn check_algorithm(&self, algorithm: Algorithm) -> Result<(), JwtError> {
+    let allowed = self
+      .allowed_algorithms
+      .as_ref()
+      .is_none_or(|allowed| allowed.contains(&algorithm));
+    if !allowed {
+      return Err(JwtError::AlgorithmMismatch);
+    }
+    Ok(())
+  }
+
+  /// Comprueba el `typ` y los parámetros críticos de la cabecera del token.
+  pub fn check_header(&self, header: &TokenHeader) -> Result<(), JwtError> {
+    self.check_type(header)?;
+    self.critical_headers.check(header)
+  }
+
+  // Igual que `check_header` para un JWS separado, que entiende `b64`
+  pub(crate) fn check_detached_header(
+    &self,
+    header: &TokenHeader,
+  ) -> Result<(), JwtError> {
+    self.check_type(header)?;
+    self.critical_headers.check_understood(header, &[jws::B64])
+  }
+
+  fn check_type(&self, header: &TokenHeader) -> Result<(), JwtError> {
+    match &self.required_type {
+      Some(required_type) if !header.has_type(required_type) => {
+        Err(JwtError::TypeMismatch)
+      }
+      _ => Ok(()),
+    }
+  }
+}
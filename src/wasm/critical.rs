@@ -0,0 +1,90 @@
+use crate::{JwtError, TokenHeader, VerifyOptions};
+use js_sys::{Array, Function, Object, Reflect};
+use serde::Serialize;
+use serde_wasm_bindgen::from_value;
+use std::ops::Deref;
+use wasm_bindgen::prelude::*;
+
+const CRITICAL_HEADERS: &str = "critical_headers";
+
+/// Opciones de validación recibidas desde JS
+///
+/// Las funciones de `critical_headers` no pueden cruzar a las
+/// `VerifyOptions`, así que sus parámetros se registran allí como aceptados
+/// y la función JS se llama después, con la firma ya verificada y antes de
+/// devolver nada del token.
+pub(super) struct JsVerifyOptions {
+  options: VerifyOptions,
+  handlers: Vec<(String, Function)>,
+}
+impl JsVerifyOptions {
+  pub(super) fn parse(options: JsValue) -> Result<Self, JwtError> {
+    if options.is_undefined() || options.is_null() {
+      return Ok(Self { options: VerifyOptions::default(), handlers: vec![] });
+    }
+    let handlers = Reflect::get(&options, &CRITICAL_HEADERS.into())
+      .unwrap_or(JsValue::UNDEFINED);
+    if handlers.is_undefined() || handlers.is_null() {
+      let options = from_value(options)
+        .map_err(|err| JwtError::InvalidOptions(err.to_string()))?;
+      return Ok(Self { options, handlers: vec![] });
+    }
+
+    // Se deserializa una copia de las opciones sin las funciones
+    let copy = Object::assign(&Object::new(), &Object::from(options));
+    let _ = Reflect::delete_property(&copy, &CRITICAL_HEADERS.into());
+    let mut options: VerifyOptions = from_value(copy.into())
+      .map_err(|err| JwtError::InvalidOptions(err.to_string()))?;
+    let handlers = Object::entries(&Object::from(handlers))
+      .iter()
+      .map(|entry| {
+        let entry = Array::from(&entry);
+        let name = entry.get(0).as_string().unwrap_or_default();
+        let handler = entry.get(1).dyn_into::<Function>().map_err(|_| {
+          JwtError::InvalidOptions(format!(
+            "The critical header handler for `{name}` must be a function"
+          ))
+        })?;
+        options.critical_headers.accept(&name)?;
+        Ok((name, handler))
+      })
+      .collect::<Result<_, JwtError>>()?;
+    Ok(Self { options, handlers })
+  }
+
+  /// Llama a las funciones JS de los parámetros críticos de la cabecera.
+  /// Si una lanza una excepción o devuelve `false`, el token se rechaza.
+  pub(super) fn check_critical(
+    &self,
+    header: &TokenHeader,
+  ) -> Result<(), JwtError> {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    for name in header.crit.iter().flatten() {
+      let Some((_, handler)) =
+        self.handlers.iter().find(|(key, _)| key == name)
+      else {
+        continue;
+      };
+      let value = header.extra[name]
+        .serialize(&serializer)
+        .map_err(|err| JwtError::Internal(err.to_string()))?;
+      let full_header = header
+        .serialize(&serializer)
+        .map_err(|err| JwtError::Internal(err.to_string()))?;
+      let accepted = handler
+        .call2(&JsValue::NULL, &value, &full_header)
+        .is_ok_and(|result| result.as_bool() != Some(false));
+      if !accepted {
+        return Err(JwtError::CriticalRejected { parameter: name.clone() });
+      }
+    }
+    Ok(())
+  }
+}
+impl Deref for JsVerifyOptions {
+  type Target = VerifyOptions;
+
+  fn deref(&self) -> &VerifyOptions {
+    &self.options
+  }
+}
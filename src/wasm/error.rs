@@ -0,0 +1,34 @@
+use crate::JwtError;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(inline_js = r#"
+export class JwtError extends Error {
+  constructor(code, message, details) {
+    super(message);
+    this.name = "JwtError";
+    this.code = code;
+    this.details = details;
+  }
+}
+"#)]
+extern "C" {
+  #[wasm_bindgen(js_name = JwtError)]
+  type JsJwtError;
+
+  #[wasm_bindgen(constructor, js_class = "JwtError")]
+  fn new(code: &str, message: &str, details: JsValue) -> JsJwtError;
+}
+
+/// En JS el error se lanza como una instancia de `JwtError`, subclase de
+/// `Error` con las propiedades `code`, `message` y `details`.
+impl From<JwtError> for JsValue {
+  fn from(err: JwtError) -> Self {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    let details = err
+      .details()
+      .and_then(|details| details.serialize(&serializer).ok())
+      .unwrap_or(JsValue::UNDEFINED);
+    JsJwtError::new(err.code(), &err.to_string(), details).into()
+  }
+}
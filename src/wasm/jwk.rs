@@ -0,0 +1,39 @@
+use super::{parse_verify_options, verify_full, verify_payload};
+use crate::{JwkSet, JwtError};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl JwkSet {
+  // static methods
+  /// Acepta el documento JWKS como cadena JSON o como objeto.
+  #[wasm_bindgen(constructor)]
+  pub fn from_js(jwks: JsValue) -> Result<JwkSet, JwtError> {
+    match jwks.as_string() {
+      Some(json) => Self::from_json(&json),
+      None => serde_wasm_bindgen::from_value(jwks)
+        .map_err(|err| JwtError::InvalidKey(err.to_string())),
+    }
+  }
+  // instance methods
+  pub fn verify(
+    &self,
+    token: &str,
+    options: JsValue,
+  ) -> Result<JsValue, JwtError> {
+    let options = parse_verify_options(options)?;
+    verify_payload(&self.verifying_key(token)?, token, &options)
+  }
+  /// Devuelve `{ header, claims, payload }` como `verify_jwt_full`.
+  pub fn verify_full(
+    &self,
+    token: &str,
+    options: JsValue,
+  ) -> Result<JsValue, JwtError> {
+    let options = parse_verify_options(options)?;
+    verify_full(&self.verifying_key(token)?, token, &options)
+  }
+  pub fn to_json(&self) -> Result<String, JwtError> {
+    serde_json::to_string(self)
+      .map_err(|err| JwtError::Internal(err.to_string()))
+  }
+}
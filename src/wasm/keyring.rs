@@ -0,0 +1,83 @@
+use super::{
+  parse_key_material, parse_options, parse_payload, parse_verify_options,
+  verify_full, verify_payload,
+};
+use crate::{duration, Algorithm, JwtError, KeyRing, Secret, SigningKey};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl KeyRing {
+  // static methods
+  /// El periodo de gracia puede ser un número de milisegundos o una cadena
+  /// como `"7d"`; por defecto las claves retiradas se rechazan enseguida.
+  #[wasm_bindgen(constructor)]
+  pub fn new(grace_period: JsValue) -> Result<KeyRing, JwtError> {
+    let grace_period = if let Some(grace_period) = grace_period.as_string() {
+      duration::parse_millis(&grace_period).map_err(JwtError::InvalidOptions)?
+    } else if let Some(grace_period) = grace_period.as_f64() {
+      duration::float_millis(grace_period)
+        .map_err(|err| JwtError::InvalidOptions(err.to_string()))?
+    } else {
+      0
+    };
+    Ok(Self::with_grace_period(grace_period))
+  }
+  // instance methods
+  /// Con un algoritmo HMAC `key` es el secreto; con el resto, la clave
+  /// privada (para firmar y verificar) o la pública (solo para verificar) en
+  /// PEM, DER o JWK.
+  pub fn add_key(
+    &mut self,
+    kid: &str,
+    algorithm: &str,
+    key: JsValue,
+    not_before: Option<u64>,
+    not_after: Option<u64>,
+  ) -> Result<(), JwtError> {
+    let algorithm: Algorithm = algorithm.parse()?;
+    if let Some(secret) = key.as_string().filter(|_| algorithm.is_hmac()) {
+      let secret = Secret::from(secret);
+      let key = SigningKey::from_secret(algorithm, secret.expose().as_bytes())?;
+      return self.add_signing_key(kid, key, not_before, not_after);
+    }
+    let material = parse_key_material(key)?;
+    self.add_material(kid, algorithm, &material, not_before, not_after)
+  }
+  pub fn remove_key(&mut self, kid: &str) -> bool {
+    self.remove(kid)
+  }
+  pub fn get_active_key_id(&self) -> Result<String, JwtError> {
+    Ok(self.active_key()?.kid().to_string())
+  }
+  /// Firma con la clave activa; el `secret`, el `algorithm` y el `key_id` de
+  /// las opciones se ignoran.
+  #[wasm_bindgen(js_name = sign)]
+  pub fn sign_payload(
+    &self,
+    payload: JsValue,
+    options: JsValue,
+  ) -> Result<String, JwtError> {
+    let payload = parse_payload(payload)?;
+    let options = parse_options(options)?;
+    self.sign_with_header(options.claims(payload)?, options.token_header()?)
+  }
+  #[wasm_bindgen(js_name = verify)]
+  pub fn verify_token(
+    &self,
+    token: &str,
+    options: JsValue,
+  ) -> Result<JsValue, JwtError> {
+    let options = parse_verify_options(options)?;
+    verify_payload(self.verifying_key(token)?, token, &options)
+  }
+  /// Devuelve `{ header, claims, payload }` como `verify_jwt_full`.
+  #[wasm_bindgen(js_name = verify_full)]
+  pub fn verify_token_full(
+    &self,
+    token: &str,
+    options: JsValue,
+  ) -> Result<JsValue, JwtError> {
+    let options = parse_verify_options(options)?;
+    verify_full(self.verifying_key(token)?, token, &options)
+  }
+}
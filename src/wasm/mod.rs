@@ -0,0 +1,862 @@
+mod critical;
+mod error;
+mod jwk;
+mod keyring;
+mod options;
+mod signer;
+mod verifier;
+
+pub use options::JsJwtOptions;
+pub use signer::JwtSigner;
+pub use verifier::JwtVerifier;
+
+use crate::core::{
+  self, allowed_token_algorithm, private_signing_key, secret_signing_key,
+  secret_verifying_key, JwtOptions,
+};
+use crate::{
+  generate_secret, sign_and_encrypt, Algorithm, DecryptOptions, DecryptionKey,
+  EncryptionKey, GeneratedKey, JweHeader, JweOptions, Jwk, JwkSet, JwtError,
+  KeyGenOptions, KeyMaterial, Secret, SigningKey, TokenHeader, UnverifiedToken,
+  VerifyOptions, VerifyingKey,
+};
+use critical::JsVerifyOptions;
+use js_sys::Uint8Array;
+use jwt_simple::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+use serde_wasm_bindgen::{from_value, to_value};
+use wasm_bindgen::prelude::*;
+
+/// 📌 Crea un JWT personalizado
+///
+/// ### Arguments
+///
+/// - `payload` - Un objeto JSON con los datos a incluir en el JWT.
+/// - `options` - Un objeto JSON con opciones como la clave secreta, la duración
+///   y el algoritmo (`"HS256"` por defecto, `"HS384"` o `"HS512"`). La
+///   duración (`expires_in`) puede ser un número de milisegundos, una cadena
+///   como `"15m"` o `"7d"`, o un objeto `{ seconds }`. Opcionalmente también
+///   `issuer`, `subject`, `audience` (una o varias), `jwt_id` (o
+///   `generate_jwt_id: true`), `not_before`, con el mismo formato que la
+///   duración, y `key_id` (o `kid`), que se añade a la cabecera del token.
+///   La cabecera admite además `typ` (`"JWT"` por defecto; por ejemplo
+///   `"at+jwt"`), `cty` y `header`, un objeto con otros parámetros como
+///   `jku`, `x5t#S256` o parámetros privados. `alg` no se puede fijar.
+///   `crit` lista los parámetros de `header` que el verificador debe
+///   entender para aceptar el token.
+///
+/// El secreto no tiene valor por defecto y debe tener al menos tantos bytes
+/// como el hash del algoritmo: 32 (`HS256`), 48 (`HS384`) o 64 (`HS512`).
+/// `generate_secret` genera uno adecuado; en pruebas, `allow_weak_secret:
+/// true` desactiva la comprobación.
+///
+/// ### Returns
+///
+/// - Devuelve un `String` con el JWT generado.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function create_jwt(payload: Record<string, any>, options: JwtOptions): string;
+/// ```
+#[wasm_bindgen]
+pub fn create_jwt(
+  payload: JsValue,
+  options: JsValue,
+) -> Result<String, JwtError> {
+  let payload = parse_payload(payload)?;
+  let jwt_options = parse_options(options)?;
+
+  // Genera el JWT con la clave del algoritmo elegido
+  let key = secret_signing_key(&jwt_options)?;
+  core::sign(&payload, &key, &jwt_options)
+}
+
+/// 📌 Crea un JWT firmado con una clave privada
+///
+/// ### Arguments
+///
+/// - `payload` - Un objeto JSON con los datos a incluir en el JWT.
+/// - `private_key` - La clave privada como `string` PEM (PKCS#1, PKCS#8 o
+///   SEC1) o como `Uint8Array` con los bytes DER. Las claves EC también
+///   admiten el escalar privado en bruto y las Ed25519 la semilla de 32
+///   bytes. También se acepta un objeto JWK privado (`RSA`, `EC` u `OKP`).
+/// - `options` - Un objeto JSON con la duración y el algoritmo: `"RS256"`,
+///   `"RS384"`, `"RS512"` (PKCS#1 v1.5), `"PS256"`, `"PS384"`, `"PS512"`
+///   (RSASSA-PSS), `"ES256"`, `"ES384"`, `"ES256K"` (ECDSA, con la firma
+///   en formato JOSE `r||s`) o `"EdDSA"` (Ed25519). El campo `secret` se
+///   ignora.
+///
+/// ### Returns
+///
+/// - Devuelve un `String` con el JWT generado.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function create_jwt_with_key(payload: Record<string, any>, private_key: string | Uint8Array | Jwk, options: JwtOptions): string;
+/// ```
+#[wasm_bindgen]
+pub fn create_jwt_with_key(
+  payload: JsValue,
+  private_key: JsValue,
+  options: JsValue,
+) -> Result<String, JwtError> {
+  let material = parse_key_material(private_key)?;
+  let payload = parse_payload(payload)?;
+  let jwt_options = parse_options(options)?;
+
+  let key = private_signing_key(&jwt_options, &material)?;
+  core::sign(&payload, &key, &jwt_options)
+}
+
+/// 📌 Verifica el JWT y devuelve el payload decodificado
+///
+/// ### Arguments
+///
+/// - `token` - Una cadena con el token JWT.
+/// - `secret` - El secreto de la clave de autenticación, o un JWKS
+///   (`{ keys: [...] }`) del que se elige la clave por el `kid` y el `alg`
+///   del token.
+/// - `options` - Opcional. Un objeto JSON con las reglas de validación:
+///   `allowed_issuers`, `allowed_audiences`, `required_subject`,
+///   `required_jwt_id`, `required_type` (el `typ` exigido, como `"at+jwt"`),
+///   `leeway` (tolerancia de reloj, 15 minutos por defecto) y `max_age`. El
+///   secreto debe cumplir la misma longitud mínima que en `create_jwt`, salvo
+///   con `allow_weak_secret: true`. `critical_headers` asocia a cada parámetro
+///   crítico (`crit`) que se entiende una función `(value, header) =>
+///   boolean`; si lanza una excepción o devuelve `false`, el error es
+///   `CriticalRejected`, y un parámetro crítico sin función se rechaza con
+///   `UnsupportedCritical`.
+///
+/// Con un secreto, el algoritmo HMAC (`HS256`, `HS384` o `HS512`) se toma de
+/// la cabecera del token, de modo que la clave usada coincide con la que lo
+/// firmó.
+///
+/// ### Returns
+///
+/// - Devuelve un `Map<string, any>` con el payload deserializado.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function verify_jwt(token: string, secret: string | JwkSet, options?: VerifyOptions): Map<string, any>;
+/// ```
+#[wasm_bindgen]
+pub fn verify_jwt(
+  token: &str,
+  secret: JsValue,
+  options: JsValue,
+) -> Result<JsValue, JwtError> {
+  let verify_options = parse_verify_options(options)?;
+
+  let key = secret_or_jwks_key(token, secret, &verify_options)?;
+  verify_payload(&key, token, &verify_options)
+}
+
+/// 📌 Verifica un JWT firmado con una clave privada usando su clave pública
+///
+/// ### Arguments
+///
+/// - `token` - Una cadena con el token JWT.
+/// - `public_key` - La clave pública como `string` PEM (PKCS#1 o SPKI) o
+///   como `Uint8Array` con los bytes DER. Las claves EC también admiten el
+///   punto público comprimido o sin comprimir y las Ed25519 los 32 bytes en
+///   bruto. También se acepta un objeto JWK, o un JWKS (`{ keys: [...] }`)
+///   del que se elige la clave por el `kid` y el `alg` del token.
+/// - `options` - Opcional. Las mismas reglas de validación que `verify_jwt`.
+///
+/// ### Returns
+///
+/// - Devuelve un `Map<string, any>` con el payload deserializado.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function verify_jwt_with_key(token: string, public_key: string | Uint8Array | Jwk | JwkSet, options?: VerifyOptions): Map<string, any>;
+/// ```
+#[wasm_bindgen]
+pub fn verify_jwt_with_key(
+  token: &str,
+  public_key: JsValue,
+  options: JsValue,
+) -> Result<JsValue, JwtError> {
+  let verify_options = parse_verify_options(options)?;
+
+  let key = public_or_jwks_key(token, public_key, &verify_options)?;
+  verify_payload(&key, token, &verify_options)
+}
+
+/// 📌 Verifica el JWT y devuelve la cabecera y todos sus claims
+///
+/// ### Arguments
+///
+/// - `token` - Una cadena con el token JWT.
+/// - `secret` - El secreto de la clave de autenticación o un JWKS, igual que
+///   en `verify_jwt`.
+/// - `options` - Opcional. Las mismas reglas de validación que `verify_jwt`.
+///
+/// ### Returns
+///
+/// - Devuelve un objeto `{ header, claims, payload }`: la cabecera JOSE, los
+///   claims registrados (con `iat`, `exp` y `nbf` en segundos Unix) y el
+///   payload personalizado.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function verify_jwt_full(token: string, secret: string | JwkSet, options?: VerifyOptions): VerifiedToken;
+/// ```
+#[wasm_bindgen]
+pub fn verify_jwt_full(
+  token: &str,
+  secret: JsValue,
+  options: JsValue,
+) -> Result<JsValue, JwtError> {
+  let verify_options = parse_verify_options(options)?;
+
+  let key = secret_or_jwks_key(token, secret, &verify_options)?;
+  verify_full(&key, token, &verify_options)
+}
+
+/// 📌 Verifica el JWT con una clave pública y devuelve la cabecera y todos
+/// sus claims
+///
+/// ### Arguments
+///
+/// - `token` - Una cadena con el token JWT.
+/// - `public_key` - La clave pública, en los mismos formatos que
+///   `verify_jwt_with_key`.
+/// - `options` - Opcional. Las mismas reglas de validación que `verify_jwt`.
+///
+/// ### Returns
+///
+/// - Devuelve un objeto `{ header, claims, payload }`, igual que
+///   `verify_jwt_full`.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function verify_jwt_with_key_full(token: string, public_key: string | Uint8Array | Jwk | JwkSet, options?: VerifyOptions): VerifiedToken;
+/// ```
+#[wasm_bindgen]
+pub fn verify_jwt_with_key_full(
+  token: &str,
+  public_key: JsValue,
+  options: JsValue,
+) -> Result<JsValue, JwtError> {
+  let verify_options = parse_verify_options(options)?;
+
+  let key = public_or_jwks_key(token, public_key, &verify_options)?;
+  verify_full(&key, token, &verify_options)
+}
+
+/// 📌 Decodifica un JWT SIN verificar la firma
+///
+/// ### Arguments
+///
+/// - `token` - Una cadena con el token JWT.
+///
+/// ### Returns
+///
+/// - Devuelve un objeto `{ verified: false, header, claims, payload }`. Su
+///   contenido no es de fiar; úsalo solo para elegir la clave (`kid`, `iss`)
+///   o para depurar, y verifica siempre el token antes de usar sus datos.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function decode_jwt(token: string): UnverifiedToken;
+/// ```
+#[wasm_bindgen]
+pub fn decode_jwt(token: &str) -> Result<JsValue, JwtError> {
+  let decoded = UnverifiedToken::decode(token)?;
+  let value =
+    to_value(&decoded).map_err(|err| JwtError::Internal(err.to_string()))?;
+  with_plain_header(value, &decoded.header)
+}
+
+/// 📌 Firma un payload y lo deja fuera del token (JWS separado, RFC 7797)
+///
+/// ### Arguments
+///
+/// - `payload` - Los bytes a firmar, como `Uint8Array` o como `string` (que
+///   se firma en UTF-8), por ejemplo el cuerpo de un webhook. Se firma tal
+///   cual, sin codificar en base64url (`b64: false`).
+/// - `options` - Las mismas opciones que en `create_jwt`: el `secret` o la
+///   clave, el `algorithm`, `key_id` y los parámetros de la cabecera (`typ`,
+///   `cty`, `header`, `crit`). Los claims (`expires_in`, `issuer`...) no se
+///   usan, y `typ` no tiene valor por defecto.
+/// - `private_key` - Opcional. La clave privada, como en
+///   `create_jwt_with_key`; sin ella se firma con el `secret`.
+///
+/// ### Returns
+///
+/// - Devuelve un `String` con la forma `cabecera..firma`; el payload debe
+///   enviarse aparte.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function create_detached_jws(payload: Uint8Array | string, options: JwtOptions, private_key?: string | Uint8Array | Jwk): string;
+/// ```
+#[wasm_bindgen]
+pub fn create_detached_jws(
+  payload: JsValue,
+  options: JsValue,
+  private_key: JsValue,
+) -> Result<String, JwtError> {
+  let payload = parse_bytes(payload)?;
+  let jwt_options = parse_options(options)?;
+
+  let key = if private_key.is_undefined() || private_key.is_null() {
+    secret_signing_key(&jwt_options)?
+  } else {
+    private_signing_key(&jwt_options, &parse_key_material(private_key)?)?
+  };
+  core::sign_detached(&payload, &key, &jwt_options)
+}
+
+/// 📌 Verifica un JWS separado con el payload que viaja aparte
+///
+/// ### Arguments
+///
+/// - `token` - El JWS con la forma `cabecera..firma`.
+/// - `payload` - Los bytes recibidos, como `Uint8Array` o `string` (UTF-8),
+///   exactamente como se firmaron.
+/// - `key` - El secreto (con algoritmos HMAC), la clave pública en PEM, DER
+///   o JWK, o un JWKS.
+/// - `options` - Opcional. Se aplican `allowed_algorithms`, `required_type`,
+///   `critical_headers` y `allow_weak_secret`; no hay claims que validar.
+///
+/// Acepta payloads sin codificar (`b64: false`, que debe estar en `crit`) y
+/// codificados en base64url.
+///
+/// ### Returns
+///
+/// - Devuelve la cabecera del JWS.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function verify_detached_jws(token: string, payload: Uint8Array | string, key: string | Uint8Array | Jwk | JwkSet, options?: VerifyOptions): TokenHeader;
+/// ```
+#[wasm_bindgen]
+pub fn verify_detached_jws(
+  token: &str,
+  payload: JsValue,
+  key: JsValue,
+  options: JsValue,
+) -> Result<JsValue, JwtError> {
+  let payload = parse_bytes(payload)?;
+  let verify_options = parse_verify_options(options)?;
+
+  let key = secret_or_public_key(token, key, &verify_options)?;
+  let header = core::verify_detached(token, &payload, &key, &verify_options)?;
+  verify_options.check_critical(&header)?;
+  to_json_value(&header)
+}
+
+/// 📌 Crea un JWT cifrado (JWE en serialización compacta)
+///
+/// ### Arguments
+///
+/// - `payload` - Un objeto JSON con los datos a incluir en el JWT.
+/// - `key` - La clave del destinatario. Con `dir`, `A128KW` y `A256KW`, la
+///   clave compartida como `Uint8Array` o JWK `oct`: 16 o 32 bytes según el
+///   algoritmo, o con `dir` tantos como pida `enc`. Con `RSA-OAEP-256`, la
+///   clave pública RSA, y con `ECDH-ES`, la pública P-256, en PEM, DER o
+///   JWK.
+/// - `options` - Las mismas opciones de claims que `create_jwt` (duración,
+///   `issuer`, `audience`...). `secret`, `algorithm` y `key_id` se ignoran.
+/// - `encryption` - Un objeto `{ alg, enc?, kid? }` con la gestión de la
+///   clave, el cifrado del contenido (`"A256GCM"` por defecto) y el `kid` de
+///   la cabecera.
+///
+/// El contenido solo lo puede leer quien tenga la clave de descifrado, pero
+/// con claves públicas cualquiera puede crear un JWE válido: si además hay
+/// que probar quién lo emitió, el token debe ir firmado.
+///
+/// ### Returns
+///
+/// - Devuelve un `String` con el JWE generado.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function encrypt_jwt(payload: Record<string, any>, key: Uint8Array | string | Jwk, options: JwtOptions, encryption: JweOptions): string;
+/// ```
+#[wasm_bindgen]
+pub fn encrypt_jwt(
+  payload: JsValue,
+  key: JsValue,
+  options: JsValue,
+  encryption: JsValue,
+) -> Result<String, JwtError> {
+  let payload = parse_payload(payload)?;
+  let jwt_options = parse_options(options)?;
+  let encryption = parse_encryption_options(encryption)?;
+
+  let key =
+    EncryptionKey::from_material(encryption.alg, &parse_key_material(key)?)?;
+  let claims = serde_json::to_vec(&jwt_options.claims(payload)?)
+    .map_err(|err| JwtError::Internal(err.to_string()))?;
+  let header =
+    JweHeader { typ: Some("JWT".to_string()), ..encryption.header() };
+  key.encrypt(header, &claims)
+}
+
+/// 📌 Descifra un JWT cifrado (JWE) y devuelve el payload
+///
+/// ### Arguments
+///
+/// - `token` - Una cadena con el JWE.
+/// - `key` - La clave de descifrado: la clave compartida (`Uint8Array` o JWK
+///   `oct`) o la clave privada RSA o P-256 en PEM, DER o JWK. El algoritmo se
+///   toma de la cabecera `alg` y debe corresponder al tipo de clave.
+/// - `options` - Opcional. Las mismas reglas de validación de claims que
+///   `verify_jwt`.
+/// - `decryption` - Opcional. Los `alg` (`allowed_algorithms`) y `enc`
+///   (`allowed_encryptions`) aceptados, que se comprueban antes de importar
+///   la clave; por defecto se acepta cualquiera que corresponda a la clave.
+///
+/// ### Returns
+///
+/// - Devuelve un `Map` con el payload descifrado.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///   Si la clave no es la correcta o el token se ha manipulado, el código es
+///   `DecryptionFailed`.
+///
+/// ```typescript
+/// export function decrypt_jwt(token: string, key: Uint8Array | string | Jwk, options?: VerifyOptions, decryption?: DecryptOptions): Map<string, any>;
+/// ```
+#[wasm_bindgen]
+pub fn decrypt_jwt(
+  token: &str,
+  key: JsValue,
+  options: JsValue,
+  decryption: JsValue,
+) -> Result<JsValue, JwtError> {
+  let verify_options = parse_verify_options(options)?;
+  let decrypt_options = parse_decrypt_options(decryption)?;
+
+  let key = allowed_decryption_key(token, key, &decrypt_options)?;
+  let (_, plaintext) = key.decrypt(token)?;
+  let claims: JWTClaims<Value> = serde_json::from_slice(&plaintext)
+    .map_err(|err| JwtError::Malformed(err.to_string()))?;
+  verify_options.validate(&claims)?;
+
+  to_value(&claims.custom).map_err(|err| JwtError::Internal(err.to_string()))
+}
+
+/// 📌 Crea un JWT anidado: lo firma y después lo cifra
+///
+/// ### Arguments
+///
+/// - `payload` - Un objeto JSON con los datos a incluir en el JWT.
+/// - `signing_key` - La clave privada que firma el token interno, en los
+///   mismos formatos que `create_jwt_with_key`. Con `null` o `undefined` se
+///   firma con el `secret` de las opciones, como en `create_jwt`.
+/// - `encryption_key` - La clave del destinatario, como en `encrypt_jwt`.
+/// - `options` - Las opciones del token firmado, como en `create_jwt`:
+///   algoritmo de firma, duración, claims registrados, `key_id` y la
+///   cabecera del JWS interior (`typ`, `cty`, `header`, `crit`).
+/// - `encryption` - Las opciones del JWE exterior, como en `encrypt_jwt`.
+///
+/// ### Returns
+///
+/// - Devuelve un `String` con el JWE, cuya cabecera lleva `cty: "JWT"`.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function create_nested_jwt(payload: Record<string, any>, signing_key: string | Uint8Array | Jwk | null | undefined, encryption_key: Uint8Array | string | Jwk, options: JwtOptions, encryption: JweOptions): string;
+/// ```
+#[wasm_bindgen]
+pub fn create_nested_jwt(
+  payload: JsValue,
+  signing_key: JsValue,
+  encryption_key: JsValue,
+  options: JsValue,
+  encryption: JsValue,
+) -> Result<String, JwtError> {
+  let payload = parse_payload(payload)?;
+  let jwt_options = parse_options(options)?;
+  let encryption = parse_encryption_options(encryption)?;
+
+  let signing_key = if signing_key.is_undefined() || signing_key.is_null() {
+    secret_signing_key(&jwt_options)?
+  } else {
+    private_signing_key(&jwt_options, &parse_key_material(signing_key)?)?
+  };
+  let encryption_key = EncryptionKey::from_material(
+    encryption.alg,
+    &parse_key_material(encryption_key)?,
+  )?;
+  sign_and_encrypt(
+    jwt_options.claims(payload)?,
+    &signing_key,
+    jwt_options.token_header()?,
+    &encryption_key,
+    encryption.header(),
+  )
+}
+
+/// 📌 Descifra un JWT anidado, verifica su firma y devuelve la cabecera y
+/// todos sus claims
+///
+/// ### Arguments
+///
+/// - `token` - Una cadena con el JWE, con `cty: "JWT"` en la cabecera.
+/// - `decryption_key` - La clave de descifrado, como en `decrypt_jwt`.
+/// - `verifying_key` - La clave que verifica la firma del token interno: el
+///   secreto (`string`) con HMAC, o la clave pública o un JWKS, como en
+///   `verify_jwt_with_key`.
+/// - `options` - Opcional. Las reglas de validación del token interno, como
+///   en `verify_jwt`.
+/// - `decryption` - Opcional. Las reglas del JWE exterior:
+///   `allowed_algorithms` (`alg` aceptados) y `allowed_encryptions` (`enc`
+///   aceptados).
+///
+/// ### Returns
+///
+/// - Devuelve un objeto `{ header, claims, payload }` con la cabecera del
+///   token firmado, igual que `verify_jwt_full`.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function verify_nested_jwt(token: string, decryption_key: Uint8Array | string | Jwk, verifying_key: string | Uint8Array | Jwk | JwkSet, options?: VerifyOptions, decryption?: DecryptOptions): VerifiedToken;
+/// ```
+#[wasm_bindgen]
+pub fn verify_nested_jwt(
+  token: &str,
+  decryption_key: JsValue,
+  verifying_key: JsValue,
+  options: JsValue,
+  decryption: JsValue,
+) -> Result<JsValue, JwtError> {
+  let verify_options = parse_verify_options(options)?;
+  let decrypt_options = parse_decrypt_options(decryption)?;
+
+  let decryption_key =
+    allowed_decryption_key(token, decryption_key, &decrypt_options)?;
+  let jws = decryption_key.decrypt_nested(token, &decrypt_options)?;
+  let verifying_key =
+    secret_or_public_key(&jws, verifying_key, &verify_options)?;
+  verify_full(&verifying_key, &jws, &verify_options)
+}
+
+/// 📌 Exporta una clave privada (o un secreto HMAC) como JWK
+///
+/// ### Arguments
+///
+/// - `private_key` - El secreto HMAC como `string`, o la clave privada en
+///   cualquiera de los formatos que acepta `create_jwt_with_key`.
+/// - `algorithm` - El algoritmo al que queda vinculada la clave (`alg`).
+/// - `kid` - Opcional. El identificador de la clave.
+///
+/// ### Returns
+///
+/// - Devuelve un objeto JWK con los miembros privados incluidos.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function export_private_jwk(private_key: string | Uint8Array | Jwk, algorithm: string, kid?: string): Jwk;
+/// ```
+#[wasm_bindgen]
+pub fn export_private_jwk(
+  private_key: JsValue,
+  algorithm: &str,
+  kid: Option<String>,
+) -> Result<JsValue, JwtError> {
+  let algorithm: Algorithm = algorithm.parse()?;
+  let key = match private_key.as_string() {
+    Some(secret) if algorithm.is_hmac() => {
+      let secret = Secret::from(secret);
+      SigningKey::from_secret(algorithm, secret.expose().as_bytes())?
+    }
+    _ => {
+      SigningKey::from_material(algorithm, &parse_key_material(private_key)?)?
+    }
+  };
+  to_json_value(&key.to_jwk()?.with_key_id(kid))
+}
+
+/// 📌 Exporta la parte pública de una clave como JWK
+///
+/// ### Arguments
+///
+/// - `key` - La clave pública o privada en PEM, DER o JWK. De una clave
+///   privada solo se exporta su parte pública.
+/// - `algorithm` - El algoritmo al que queda vinculada la clave (`alg`).
+/// - `kid` - Opcional. El identificador de la clave.
+///
+/// ### Returns
+///
+/// - Devuelve un objeto JWK sin miembros privados.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function export_public_jwk(key: string | Uint8Array | Jwk, algorithm: string, kid?: string): Jwk;
+/// ```
+#[wasm_bindgen]
+pub fn export_public_jwk(
+  key: JsValue,
+  algorithm: &str,
+  kid: Option<String>,
+) -> Result<JsValue, JwtError> {
+  to_json_value(&public_jwk(key, algorithm, kid)?)
+}
+
+/// 📌 Exporta varias claves públicas como un JWKS
+///
+/// ### Arguments
+///
+/// - `keys` - Un array de objetos `{ key, algorithm, kid? }`, con cada clave
+///   en los mismos formatos que `export_public_jwk`.
+///
+/// ### Returns
+///
+/// - Devuelve un objeto `{ keys: [...] }` listo para publicarse.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function export_jwks(keys: { key: string | Uint8Array | Jwk, algorithm: string, kid?: string }[]): JwkSet;
+/// ```
+#[wasm_bindgen]
+pub fn export_jwks(keys: js_sys::Array) -> Result<JsValue, JwtError> {
+  let keys = keys
+    .iter()
+    .map(|entry| {
+      let field = |name: &str| {
+        js_sys::Reflect::get(&entry, &JsValue::from_str(name))
+          .unwrap_or(JsValue::UNDEFINED)
+      };
+      let algorithm = field("algorithm").as_string().unwrap_or_default();
+      public_jwk(field("key"), &algorithm, field("kid").as_string())
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  to_json_value(&JwkSet::new(keys))
+}
+
+/// 📌 Genera un secreto HMAC aleatorio
+///
+/// ### Arguments
+///
+/// - `algorithm` - Opcional. El algoritmo HMAC con el que se usará. El
+///   secreto tiene tantos bytes aleatorios como el hash del algoritmo; sin
+///   algoritmo se generan 64, suficientes para cualquiera de ellos.
+///
+/// ### Returns
+///
+/// - Devuelve el secreto codificado en base64url, listo para `create_jwt`.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function generate_secret(algorithm?: string): string;
+/// ```
+#[wasm_bindgen(js_name = generate_secret)]
+pub fn generate_strong_secret(
+  algorithm: Option<String>,
+) -> Result<String, JwtError> {
+  let algorithm = match algorithm {
+    Some(algorithm) => algorithm.parse()?,
+    None => Algorithm::HS512,
+  };
+  if !algorithm.is_hmac() {
+    return Err(JwtError::InvalidOptions(format!(
+      "Algorithm {algorithm} does not use a secret"
+    )));
+  }
+  generate_secret(algorithm.hash_len())
+}
+
+/// 📌 Genera una clave nueva para el algoritmo indicado
+///
+/// ### Arguments
+///
+/// - `algorithm` - El algoritmo con el que se usará la clave.
+/// - `options` - Opcional. Un objeto JSON con `kid`, `secret_length` (bytes
+///   del secreto HMAC; por defecto la longitud del hash del algoritmo) y
+///   `modulus_bits` (tamaño RSA: 2048 por defecto, 3072 o 4096).
+///
+/// ### Returns
+///
+/// - Devuelve un objeto `{ algorithm, kid?, secret?, private_key, public_key? }`
+///   en el que cada clave es `{ pem?, jwk }`. Con HMAC, `secret` es el
+///   secreto listo para usar en `create_jwt` y no hay clave pública.
+/// - En caso de error, lanza un `JwtError` con `code`, `message` y `details`.
+///
+/// ```typescript
+/// export function generate_key(algorithm: string, options?: KeyGenOptions): GeneratedKey;
+/// ```
+#[wasm_bindgen]
+pub fn generate_key(
+  algorithm: &str,
+  options: JsValue,
+) -> Result<JsValue, JwtError> {
+  let algorithm: Algorithm = algorithm.parse()?;
+  let options: KeyGenOptions = if options.is_undefined() || options.is_null() {
+    KeyGenOptions::default()
+  } else {
+    from_value(options)
+      .map_err(|err| JwtError::InvalidOptions(err.to_string()))?
+  };
+  to_json_value(&GeneratedKey::generate(algorithm, &options)?)
+}
+
+fn parse_payload(payload: JsValue) -> Result<Value, JwtError> {
+  from_value(payload).map_err(|err| JwtError::PayloadParse(err.to_string()))
+}
+
+// Un payload binario (`Uint8Array`) o de texto (`string`, en UTF-8)
+fn parse_bytes(payload: JsValue) -> Result<Vec<u8>, JwtError> {
+  if let Some(text) = payload.as_string() {
+    return Ok(text.into_bytes());
+  }
+  if payload.is_instance_of::<Uint8Array>() {
+    return Ok(Uint8Array::new(&payload).to_vec());
+  }
+  Err(JwtError::PayloadParse(
+    "Payload must be a Uint8Array or a string".to_string(),
+  ))
+}
+
+fn parse_options(options: JsValue) -> Result<JwtOptions, JwtError> {
+  from_value(options).map_err(|err| JwtError::InvalidOptions(err.to_string()))
+}
+
+fn parse_encryption_options(options: JsValue) -> Result<JweOptions, JwtError> {
+  from_value(options).map_err(|err| JwtError::InvalidOptions(err.to_string()))
+}
+
+fn parse_decrypt_options(options: JsValue) -> Result<DecryptOptions, JwtError> {
+  if options.is_undefined() || options.is_null() {
+    return Ok(DecryptOptions::default());
+  }
+  from_value(options).map_err(|err| JwtError::InvalidOptions(err.to_string()))
+}
+
+// Importa la clave con el `alg` de la cabecera, pero solo si la cabecera
+// cumple antes las reglas de `options`
+fn allowed_decryption_key(
+  token: &str,
+  key: JsValue,
+  options: &DecryptOptions,
+) -> Result<DecryptionKey, JwtError> {
+  let header = JweHeader::decode(token)?;
+  options.check_header(&header)?;
+  DecryptionKey::from_material(header.alg, &parse_key_material(key)?)
+}
+
+// Las opciones de validación son opcionales en todas las funciones
+fn parse_verify_options(options: JsValue) -> Result<JsVerifyOptions, JwtError> {
+  JsVerifyOptions::parse(options)
+}
+
+// Acepta una clave PEM (`string`), DER (`Uint8Array`) o JWK (`object`)
+fn parse_key_material(key: JsValue) -> Result<KeyMaterial, JwtError> {
+  if let Some(pem) = key.as_string() {
+    return Ok(KeyMaterial::Pem(pem.into()));
+  }
+  if key.is_instance_of::<Uint8Array>() {
+    return Ok(KeyMaterial::Der(Uint8Array::new(&key).to_vec().into()));
+  }
+  if key.is_object() {
+    let jwk: Jwk =
+      from_value(key).map_err(|err| JwtError::InvalidKey(err.to_string()))?;
+    return Ok(KeyMaterial::Jwk(Box::new(jwk)));
+  }
+  Err(JwtError::InvalidKey(
+    "Key must be a PEM string, a Uint8Array or a JWK".to_string(),
+  ))
+}
+
+// Un secreto (`string`) o un JWKS del que se elige la clave del token
+fn secret_or_jwks_key(
+  token: &str,
+  secret: JsValue,
+  options: &VerifyOptions,
+) -> Result<VerifyingKey, JwtError> {
+  match secret.as_string() {
+    Some(secret) => secret_verifying_key(
+      allowed_token_algorithm(token, options)?,
+      secret.into(),
+      options,
+    ),
+    None => JwkSet::from_js(secret)?.verifying_key(token),
+  }
+}
+
+// Una clave pública (PEM, DER o JWK) o un JWKS con la propiedad `keys`
+fn public_or_jwks_key(
+  token: &str,
+  public_key: JsValue,
+  options: &VerifyOptions,
+) -> Result<VerifyingKey, JwtError> {
+  let is_jwk_set = public_key.is_object()
+    && js_sys::Reflect::has(&public_key, &JsValue::from_str("keys"))
+      .unwrap_or(false);
+  if is_jwk_set {
+    return JwkSet::from_js(public_key)?.verifying_key(token);
+  }
+  let algorithm = allowed_token_algorithm(token, options)?;
+  VerifyingKey::from_material(algorithm, &parse_key_material(public_key)?)
+}
+
+// Un secreto (`string`, solo con HMAC), una clave pública o un JWKS
+fn secret_or_public_key(
+  token: &str,
+  key: JsValue,
+  options: &VerifyOptions,
+) -> Result<VerifyingKey, JwtError> {
+  let algorithm = allowed_token_algorithm(token, options)?;
+  match key.as_string() {
+    Some(secret) if algorithm.is_hmac() => {
+      secret_verifying_key(algorithm, secret.into(), options)
+    }
+    _ => public_or_jwks_key(token, key, options),
+  }
+}
+
+// Exporta la parte pública de una clave, sea pública o privada
+fn public_jwk(
+  key: JsValue,
+  algorithm: &str,
+  kid: Option<String>,
+) -> Result<Jwk, JwtError> {
+  core::public_jwk(algorithm.parse()?, &parse_key_material(key)?, kid)
+}
+
+// Los JWK y los JWKS se devuelven como objetos planos, listos para JSON
+fn to_json_value<T: Serialize>(value: &T) -> Result<JsValue, JwtError> {
+  value
+    .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+    .map_err(|err| JwtError::Internal(err.to_string()))
+}
+
+fn verify_payload(
+  key: &VerifyingKey,
+  token: &str,
+  options: &JsVerifyOptions,
+) -> Result<JsValue, JwtError> {
+  let verified = core::verify_full(token, key, options)?;
+  options.check_critical(&verified.header)?;
+
+  // Convierte el payload personalizado de vuelta a JsValue
+  to_value(&verified.payload).map_err(|err| JwtError::Internal(err.to_string()))
+}
+
+fn verify_full(
+  key: &VerifyingKey,
+  token: &str,
+  options: &JsVerifyOptions,
+) -> Result<JsValue, JwtError> {
+  let verified = core::verify_full(token, key, options)?;
+  options.check_critical(&verified.header)?;
+
+  let value =
+    to_value(&verified).map_err(|err| JwtError::Internal(err.to_string()))?;
+  with_plain_header(value, &verified.header)
+}
+
+// Los parámetros adicionales aplanados harían de la cabecera un `Map`; se
+// devuelve como objeto plano, igual que los JWK
+fn with_plain_header(
+  value: JsValue,
+  header: &TokenHeader,
+) -> Result<JsValue, JwtError> {
+  js_sys::Reflect::set(&value, &"header".into(), &to_json_value(header)?)
+    .map_err(|_| JwtError::Internal("Failed to set the header".to_string()))?;
+  Ok(value)
+}
@@ -0,0 +1,96 @@
+use crate::{JwtError, JwtOptions};
+use wasm_bindgen::prelude::*;
+
+/// 📌 Opciones de firma como clase JS
+///
+/// Envuelve las `JwtOptions` de Rust para que `core` no dependa de
+/// `wasm-bindgen`.
+///
+/// ```typescript
+/// export class JwtOptions {
+///   constructor(secret: string, expires_in: bigint);
+///   get_days(): bigint;
+///   get_hours(): bigint;
+///   get_minutes(): bigint;
+///   get_seconds(): bigint;
+///   get_milliseconds(): bigint;
+///   set_issuer(issuer: string): void;
+///   set_subject(subject: string): void;
+///   set_audiences(audiences: string[]): void;
+///   set_jwt_id(jwt_id: string): void;
+///   set_generate_jwt_id(generate_jwt_id: boolean): void;
+///   set_allow_weak_secret(allow_weak_secret: boolean): void;
+///   set_key_id(key_id: string): void;
+///   set_typ(typ: string): void;
+///   set_cty(cty: string): void;
+///   set_crit(crit: string[]): void;
+///   set_expires_in(expires_in: string): void;
+///   get_algorithm(): string;
+///   set_algorithm(algorithm: string): void;
+/// }
+/// ```
+#[wasm_bindgen(js_name = JwtOptions)]
+pub struct JsJwtOptions(JwtOptions);
+#[wasm_bindgen(js_class = JwtOptions)]
+impl JsJwtOptions {
+  // static methods
+  #[wasm_bindgen(constructor)]
+  pub fn new(secret: String, expires_in: u64) -> JsJwtOptions {
+    Self(JwtOptions::new(secret, expires_in))
+  }
+  // instance methods
+  pub fn get_days(&self) -> u64 {
+    self.0.get_days()
+  }
+  pub fn get_hours(&self) -> u64 {
+    self.0.get_hours()
+  }
+  pub fn get_minutes(&self) -> u64 {
+    self.0.get_minutes()
+  }
+  pub fn get_seconds(&self) -> u64 {
+    self.0.get_seconds()
+  }
+  pub fn get_milliseconds(&self) -> u64 {
+    self.0.get_milliseconds()
+  }
+  pub fn set_issuer(&mut self, issuer: String) {
+    self.0.set_issuer(issuer);
+  }
+  pub fn set_subject(&mut self, subject: String) {
+    self.0.set_subject(subject);
+  }
+  pub fn set_audiences(&mut self, audiences: Vec<String>) {
+    self.0.set_audiences(audiences);
+  }
+  pub fn set_jwt_id(&mut self, jwt_id: String) {
+    self.0.set_jwt_id(jwt_id);
+  }
+  pub fn set_generate_jwt_id(&mut self, generate_jwt_id: bool) {
+    self.0.set_generate_jwt_id(generate_jwt_id);
+  }
+  pub fn set_allow_weak_secret(&mut self, allow_weak_secret: bool) {
+    self.0.set_allow_weak_secret(allow_weak_secret);
+  }
+  pub fn set_key_id(&mut self, key_id: String) {
+    self.0.set_key_id(key_id);
+  }
+  pub fn set_typ(&mut self, typ: String) {
+    self.0.set_typ(typ);
+  }
+  pub fn set_cty(&mut self, cty: String) {
+    self.0.set_cty(cty);
+  }
+  pub fn set_crit(&mut self, crit: Vec<String>) {
+    self.0.set_crit(crit);
+  }
+  pub fn set_expires_in(&mut self, expires_in: &str) -> Result<(), JwtError> {
+    self.0.set_expires_in(expires_in)
+  }
+  pub fn get_algorithm(&self) -> String {
+    self.0.get_algorithm()
+  }
+  pub fn set_algorithm(&mut self, algorithm: &str) -> Result<(), JwtError> {
+    self.0.set_algorithm(algorithm)
+  }
+}
@@ -0,0 +1,47 @@
+use super::{parse_key_material, parse_options, parse_payload};
+use crate::core::{self, private_signing_key, secret_signing_key};
+use crate::{JwtError, JwtOptions, SigningKey};
+use wasm_bindgen::prelude::*;
+
+/// 📌 Firmante reutilizable
+///
+/// Deserializa las opciones e importa la clave una única vez, de modo que
+/// cada llamada a `sign` solo tiene que convertir el payload.
+///
+/// ```typescript
+/// export class JwtSigner {
+///   constructor(options: JwtOptions, private_key?: string | Uint8Array | Jwk);
+///   sign(payload: Record<string, any>): string;
+/// }
+/// ```
+#[wasm_bindgen]
+pub struct JwtSigner {
+  key: SigningKey,
+  options: JwtOptions,
+}
+#[wasm_bindgen]
+impl JwtSigner {
+  // static methods
+  /// Si no se indica `private_key` se usa el `secret` de las opciones.
+  #[wasm_bindgen(constructor)]
+  pub fn new(
+    options: JsValue,
+    private_key: JsValue,
+  ) -> Result<JwtSigner, JwtError> {
+    let options = parse_options(options)?;
+    let key = if private_key.is_undefined() || private_key.is_null() {
+      secret_signing_key(&options)?
+    } else {
+      private_signing_key(&options, &parse_key_material(private_key)?)?
+    };
+    Ok(Self { key, options })
+  }
+  // instance methods
+  pub fn sign(&self, payload: JsValue) -> Result<String, JwtError> {
+    let payload = parse_payload(payload)?;
+    core::sign(&payload, &self.key, &self.options)
+  }
+  pub fn get_algorithm(&self) -> String {
+    self.key.algorithm().to_string()
+  }
+}
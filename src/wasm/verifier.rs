@@ -0,0 +1,61 @@
+use super::{
+  parse_key_material, parse_verify_options, verify_full, verify_payload,
+  JsVerifyOptions,
+};
+use crate::core::secret_verifying_key;
+use crate::{Algorithm, JwtError, VerifyingKey};
+use wasm_bindgen::prelude::*;
+
+/// 📌 Verificador reutilizable
+///
+/// Importa la clave y las opciones de validación una única vez y solo acepta
+/// tokens firmados con el algoritmo indicado al construirlo.
+///
+/// ```typescript
+/// export class JwtVerifier {
+///   constructor(key: string | Uint8Array | Jwk, algorithm?: string, options?: VerifyOptions);
+///   verify(token: string): Map<string, any>;
+///   verify_full(token: string): VerifiedToken;
+/// }
+/// ```
+#[wasm_bindgen]
+pub struct JwtVerifier {
+  key: VerifyingKey,
+  options: JsVerifyOptions,
+}
+#[wasm_bindgen]
+impl JwtVerifier {
+  // static methods
+  /// Con un algoritmo HMAC (`HS256` por defecto) `key` es el secreto; con el
+  /// resto, la clave pública en PEM, DER o JWK.
+  #[wasm_bindgen(constructor)]
+  pub fn new(
+    key: JsValue,
+    algorithm: Option<String>,
+    options: JsValue,
+  ) -> Result<JwtVerifier, JwtError> {
+    let options = parse_verify_options(options)?;
+    let algorithm: Algorithm = match algorithm {
+      Some(algorithm) => algorithm.parse()?,
+      None => Algorithm::default(),
+    };
+    let key = match key.as_string() {
+      Some(secret) if algorithm.is_hmac() => {
+        secret_verifying_key(algorithm, secret.into(), &options)?
+      }
+      _ => VerifyingKey::from_material(algorithm, &parse_key_material(key)?)?,
+    };
+    Ok(Self { key, options })
+  }
+  // instance methods
+  pub fn verify(&self, token: &str) -> Result<JsValue, JwtError> {
+    verify_payload(&self.key, token, &self.options)
+  }
+  /// Devuelve `{ header, claims, payload }` como `verify_jwt_full`.
+  pub fn verify_full(&self, token: &str) -> Result<JsValue, JwtError> {
+    verify_full(&self.key, token, &self.options)
+  }
+  pub fn get_algorithm(&self) -> String {
+    self.key.algorithm().to_string()
+  }
+}
@@ -0,0 +1,198 @@
+use jwt_simple::prelude::*;
+use jwt_wasm::{
+  token_algorithm, Algorithm, Jwk, JwkSet, JwtError, KeyMaterial, SigningKey,
+  VerifyOptions, VerifyingKey,
+};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+fn rsa_key() -> &'static SigningKey {
+  static KEY: OnceLock<SigningKey> = OnceLock::new();
+  KEY.get_or_init(|| SigningKey::generate(Algorithm::RS256, None).unwrap())
+}
+
+fn claims() -> JWTClaims<Value> {
+  Claims::with_custom_claims(json!({ "admin": true }), Duration::from_secs(300))
+}
+
+fn pem(pem: String) -> KeyMaterial {
+  KeyMaterial::Pem(pem.into())
+}
+
+fn jwk(jwk: Jwk) -> KeyMaterial {
+  KeyMaterial::Jwk(Box::new(jwk))
+}
+
+fn b64(bytes: &[u8]) -> String {
+  Base64UrlSafeNoPadding::encode_to_string(bytes).unwrap()
+}
+
+// Token sin firma con el `alg` indicado
+fn unsigned_token(alg: &str) -> String {
+  let header = json!({ "alg": alg, "typ": "JWT" }).to_string();
+  let claims = serde_json::to_string(&claims()).unwrap();
+  format!("{}.{}.", b64(header.as_bytes()), b64(claims.as_bytes()))
+}
+
+// Token HS256 firmado con la clave pública como secreto, sin pasar por las
+// comprobaciones de la librería
+fn forged_hs256_token(secret: &[u8], kid: Option<&str>) -> String {
+  let mut key = HS256Key::from_bytes(secret);
+  if let Some(kid) = kid {
+    key = key.with_key_id(kid);
+  }
+  key.authenticate(claims()).unwrap()
+}
+
+#[test]
+fn none_algorithm_is_rejected() {
+  let hmac = VerifyingKey::from_secret(Algorithm::HS256, SECRET).unwrap();
+  let rsa = rsa_key().verifying_key();
+  let options = VerifyOptions::default();
+
+  for alg in ["none", "None", "NONE"] {
+    let token = unsigned_token(alg);
+    assert_eq!(
+      token_algorithm(&token).unwrap_err(),
+      JwtError::UnsupportedAlgorithm(alg.to_string())
+    );
+    assert!(hmac.verify_with(&token, &options).is_err());
+    assert!(rsa.verify_with(&token, &options).is_err());
+  }
+}
+
+#[test]
+fn none_cannot_be_allowed() {
+  let options =
+    serde_json::from_str::<VerifyOptions>(r#"{"allowed_algorithms":["none"]}"#);
+  assert!(options.is_err());
+}
+
+#[test]
+fn rsa_public_key_cannot_be_an_hmac_secret() {
+  let public_key = rsa_key().verifying_key();
+  let public_pem = public_key.to_pem().unwrap();
+  let public_jwk = public_key.to_jwk().unwrap().to_json().unwrap();
+
+  for secret in [public_pem.as_bytes(), public_jwk.as_bytes()] {
+    assert!(VerifyingKey::from_secret(Algorithm::HS256, secret).is_err());
+    assert!(VerifyingKey::from_weak_secret(Algorithm::HS256, secret).is_err());
+    assert!(SigningKey::from_secret(Algorithm::HS256, secret).is_err());
+  }
+  assert!(
+    VerifyingKey::from_material(Algorithm::HS256, &pem(public_pem)).is_err()
+  );
+  assert!(VerifyingKey::from_material(
+    Algorithm::HS256,
+    &jwk(public_key.to_jwk().unwrap())
+  )
+  .is_err());
+}
+
+#[test]
+fn rsa_der_key_cannot_be_an_hmac_secret() {
+  let private_pem = rsa_key().to_pem().unwrap();
+  let (_, der) = sec1::pem::decode_vec(private_pem.as_bytes()).unwrap();
+  assert!(VerifyingKey::from_weak_secret(Algorithm::HS256, &der).is_err());
+}
+
+#[test]
+fn ec_public_key_cannot_be_an_hmac_secret() {
+  let public_key =
+    SigningKey::generate(Algorithm::ES256, None).unwrap().verifying_key();
+  let public_pem = public_key.to_pem().unwrap();
+  let (_, der) = sec1::pem::decode_vec(public_pem.as_bytes()).unwrap();
+
+  for secret in [public_pem.as_bytes(), &der] {
+    assert!(VerifyingKey::from_weak_secret(Algorithm::HS256, secret).is_err());
+  }
+}
+
+#[test]
+fn hs256_token_signed_with_the_public_key_is_rejected() {
+  let public_key = rsa_key().verifying_key();
+  let public_pem = public_key.to_pem().unwrap();
+  let forged = forged_hs256_token(public_pem.as_bytes(), None);
+
+  // La clave queda ligada a RS256 aunque la cabecera diga HS256
+  let rsa =
+    VerifyingKey::from_material(Algorithm::RS256, &pem(public_pem)).unwrap();
+  assert_eq!(
+    rsa.verify_with(&forged, &VerifyOptions::default()).unwrap_err(),
+    JwtError::AlgorithmMismatch
+  );
+
+  // Y si se sigue el `alg` de la cabecera, la clave no se puede importar
+  let algorithm = token_algorithm(&forged).unwrap();
+  let material = pem(public_key.to_pem().unwrap());
+  assert!(matches!(
+    VerifyingKey::from_material(algorithm, &material),
+    Err(JwtError::InvalidKey(_))
+  ));
+}
+
+#[test]
+fn jwks_key_is_not_used_as_an_hmac_secret() {
+  let public_key = rsa_key().verifying_key();
+  let mut public_jwk = public_key.to_jwk().unwrap();
+  public_jwk.kid = Some("rsa-1".to_string());
+  let jwks = JwkSet::new(vec![public_jwk]);
+
+  for secret in [
+    public_key.to_pem().unwrap().into_bytes(),
+    public_key.to_jwk().unwrap().to_json().unwrap().into_bytes(),
+  ] {
+    let forged = forged_hs256_token(&secret, Some("rsa-1"));
+    assert_eq!(
+      jwks.verifying_key(&forged).err(),
+      Some(JwtError::AlgorithmMismatch)
+    );
+  }
+}
+
+#[test]
+fn allowlist_rejects_other_algorithms() {
+  let token = rsa_key().sign(claims()).unwrap();
+  let key = rsa_key().verifying_key();
+
+  let only_ps256: VerifyOptions =
+    serde_json::from_str(r#"{"allowed_algorithms":["PS256"]}"#).unwrap();
+  assert_eq!(
+    key.verify_with(&token, &only_ps256).unwrap_err(),
+    JwtError::AlgorithmMismatch
+  );
+
+  let only_rs256: VerifyOptions =
+    serde_json::from_str(r#"{"allowed_algorithms":["RS256"]}"#).unwrap();
+  assert!(key.verify_with(&token, &only_rs256).is_ok());
+}
+
+#[test]
+fn key_is_bound_to_one_algorithm() {
+  let material = pem(rsa_key().to_pem().unwrap());
+  let ps256 = SigningKey::from_material(Algorithm::PS256, &material).unwrap();
+  let token = ps256.sign(claims()).unwrap();
+  let rs256 = rsa_key().verifying_key();
+  assert_eq!(
+    rs256.verify_with(&token, &VerifyOptions::default()).unwrap_err(),
+    JwtError::AlgorithmMismatch
+  );
+
+  let hs384 = SigningKey::from_secret(Algorithm::HS384, &[7; 48]).unwrap();
+  let token = hs384.sign(claims()).unwrap();
+  let hs256 = VerifyingKey::from_secret(Algorithm::HS256, &[7; 48]).unwrap();
+  assert_eq!(
+    hs256.verify_with(&token, &VerifyOptions::default()).unwrap_err(),
+    JwtError::AlgorithmMismatch
+  );
+}
+
+#[test]
+fn oct_jwk_holding_a_public_key_is_rejected() {
+  let public_pem = rsa_key().verifying_key().to_pem().unwrap();
+  let oct = json!({ "kty": "oct", "k": b64(public_pem.as_bytes()) });
+  let oct = Jwk::from_json(&oct.to_string()).unwrap();
+  assert!(VerifyingKey::from_material(Algorithm::HS256, &jwk(oct)).is_err());
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::{options, SECRET};
+use jwt_wasm::core::{self, secret_signing_key};
+use jwt_wasm::{Audience, JwtOptions, UnverifiedToken};
+use serde_json::json;
+
+// Firma el token y lee sus claims sin verificar
+fn sign(options: &JwtOptions) -> UnverifiedToken {
+  let key = secret_signing_key(options).unwrap();
+  let token = core::sign(&json!({ "scope": "read" }), &key, options).unwrap();
+  UnverifiedToken::decode(&token).unwrap()
+}
+
+#[test]
+fn registered_claims_are_set_from_the_options() {
+  let token = sign(&options(json!({
+    "issuer": "https://auth.example.com",
+    "subject": "user-42",
+    "audience": "api",
+    "jwt_id": "token-1",
+  })));
+  let claims = token.claims;
+  assert_eq!(claims.iss.as_deref(), Some("https://auth.example.com"));
+  assert_eq!(claims.sub.as_deref(), Some("user-42"));
+  assert!(matches!(claims.aud, Some(Audience::One(aud)) if aud == "api"));
+  assert_eq!(claims.jti.as_deref(), Some("token-1"));
+  assert_eq!(claims.exp, claims.iat.map(|iat| iat + 300));
+  assert_eq!(token.payload["scope"], "read");
+}
+
+#[test]
+fn missing_options_leave_claims_out() {
+  let token = sign(&options(json!({})));
+  let claims = token.claims;
+  assert!(claims.iss.is_none());
+  assert!(claims.sub.is_none());
+  assert!(claims.aud.is_none());
+  assert!(claims.jti.is_none());
+  // `jwt-simple` fija `nbf` al momento de la firma
+  assert_eq!(claims.nbf, claims.iat);
+}
+
+#[test]
+fn several_audiences_are_kept() {
+  let token = sign(&options(json!({ "audiences": ["api", "admin"] })));
+  let Some(Audience::Many(mut audiences)) = token.claims.aud else {
+    panic!("expected several audiences");
+  };
+  audiences.sort();
+  assert_eq!(audiences, ["admin", "api"]);
+
+  let mut options = options(json!({}));
+  options.set_audiences(vec!["billing".to_string()]);
+  let token = sign(&options);
+  assert!(
+    matches!(token.claims.aud, Some(Audience::Many(aud)) if aud == ["billing"])
+  );
+}
+
+#[test]
+fn jwt_ids_can_be_generated() {
+  let options = options(json!({ "generate_jwt_id": true }));
+  let first = sign(&options).claims.jti.unwrap();
+  let second = sign(&options).claims.jti.unwrap();
+  // 128 bits en base64url sin relleno
+  assert_eq!(first.len(), 22);
+  assert_ne!(first, second);
+
+  // Un `jwt_id` explícito tiene prioridad
+  let mut options = options;
+  options.set_jwt_id("fixed".to_string());
+  assert_eq!(sign(&options).claims.jti.as_deref(), Some("fixed"));
+}
+
+#[test]
+fn not_before_is_an_offset_from_issuance() {
+  let token = sign(&options(json!({ "not_before": "1m" })));
+  let claims = token.claims;
+  assert_eq!(claims.nbf, claims.iat.map(|iat| iat + 60));
+
+  let token = sign(&options(json!({ "not_before": 0 })));
+  assert_eq!(token.claims.nbf, token.claims.iat);
+}
+
+#[test]
+fn setters_match_the_serialized_options() {
+  let mut options = JwtOptions::new(SECRET.to_string(), 60_000);
+  options.set_issuer("issuer".to_string());
+  options.set_subject("subject".to_string());
+  let claims = sign(&options).claims;
+  assert_eq!(claims.iss.as_deref(), Some("issuer"));
+  assert_eq!(claims.sub.as_deref(), Some("subject"));
+}
@@ -0,0 +1,194 @@
+#![cfg(feature = "cli")]
+
+use jwt_simple::prelude::*;
+use jwt_wasm::{Algorithm, Jwk, KeyMaterial, SigningKey, VerifyingKey};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+fn jwt_easy(args: &[&str], stdin: &str) -> Output {
+  let mut child = Command::new(env!("CARGO_BIN_EXE_jwt-easy"))
+    .args(args)
+    .env_remove("JWT_SECRET")
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+  // Si el comando no lee stdin, la tubería puede estar ya cerrada
+  let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+  child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+  String::from_utf8(output.stdout.clone()).unwrap().trim().to_string()
+}
+
+fn json(bytes: &[u8]) -> Value {
+  serde_json::from_slice(bytes).unwrap()
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+  let path = std::env::temp_dir()
+    .join(format!("jwt-easy-{}-{name}", std::process::id()));
+  std::fs::write(&path, contents).unwrap();
+  path
+}
+
+#[test]
+fn sign_and_verify_with_a_secret() {
+  let sign = jwt_easy(
+    &["sign", "--secret", SECRET, "--iss", "ops", "--exp", "5m"],
+    r#"{"user_id":42}"#,
+  );
+  assert!(sign.status.success());
+  let token = stdout(&sign);
+
+  let verify =
+    jwt_easy(&["verify", &token, "--secret", SECRET, "--iss", "ops"], "");
+  assert!(verify.status.success());
+  let verified = json(&verify.stdout);
+  assert_eq!(verified["payload"]["user_id"], 42);
+  assert_eq!(verified["claims"]["iss"], "ops");
+
+  let decode = jwt_easy(&["decode"], &token);
+  assert_eq!(json(&decode.stdout)["verified"], false);
+}
+
+#[test]
+fn failed_verification_exits_with_a_json_error() {
+  let sign = jwt_easy(&["sign", "--secret", SECRET], "{}");
+  let token = stdout(&sign);
+
+  let verify =
+    jwt_easy(&["verify", &token, "--secret", SECRET, "--iss", "x"], "");
+  assert!(!verify.status.success());
+  assert!(verify.stdout.is_empty());
+  assert_eq!(json(&verify.stderr)["code"], "IssuerMismatch");
+
+  let other_secret = "fedcba9876543210fedcba9876543210";
+  let verify = jwt_easy(&["verify", "--secret", other_secret], &token);
+  assert_eq!(json(&verify.stderr)["code"], "InvalidSignature");
+}
+
+#[test]
+fn sign_with_a_private_key_and_verify_with_the_jwks() {
+  let key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let private_key = temp_file("private.pem", &key.to_pem().unwrap());
+  let public_key =
+    temp_file("public.pem", &key.verifying_key().to_pem().unwrap());
+  let private_key = private_key.to_str().unwrap();
+  let public_key = public_key.to_str().unwrap();
+
+  let sign = jwt_easy(
+    &["sign", "--key", private_key, "--alg", "ES256", "--kid", "ec-1"],
+    r#"{"scope":"read"}"#,
+  );
+  assert!(sign.status.success());
+  let token = stdout(&sign);
+
+  let jwks =
+    jwt_easy(&["jwks", "--alg", "ES256", "--kid", "ec-1", public_key], "");
+  let keys = json(&jwks.stdout);
+  assert_eq!(keys["keys"][0]["kid"], "ec-1");
+  assert!(keys["keys"][0].get("d").is_none());
+
+  let jwks = temp_file("jwks.json", &stdout(&jwks));
+  let verify =
+    jwt_easy(&["verify", &token, "--key", jwks.to_str().unwrap()], "");
+  assert_eq!(json(&verify.stdout)["payload"]["scope"], "read");
+}
+
+#[test]
+fn decode_prints_the_token_without_verifying_it() {
+  let sign = jwt_easy(
+    &["sign", "--secret", SECRET, "--iss", "ops", "--typ", "at+jwt"],
+    r#"{"user_id":42}"#,
+  );
+  let token = stdout(&sign);
+
+  // No hace falta el secreto, ni que la firma sea válida
+  let tampered = format!("{}x", &token[..token.len() - 1]);
+  for token in [&token, &tampered] {
+    let decode = jwt_easy(&["decode", token], "");
+    assert!(decode.status.success());
+    let decoded = json(&decode.stdout);
+    assert_eq!(decoded["verified"], false);
+    assert_eq!(decoded["header"]["alg"], "HS256");
+    assert_eq!(decoded["header"]["typ"], "at+jwt");
+    assert_eq!(decoded["claims"]["iss"], "ops");
+    assert_eq!(decoded["payload"]["user_id"], 42);
+  }
+
+  let decode = jwt_easy(&["decode", "not-a-token"], "");
+  assert!(!decode.status.success());
+  assert_eq!(json(&decode.stderr)["code"], "Malformed");
+}
+
+#[test]
+fn keygen_output_imports_as_a_key_of_the_algorithm() {
+  let keygen = jwt_easy(&["keygen", "ES256", "--kid", "ec-1"], "");
+  assert!(keygen.status.success());
+  let generated = json(&keygen.stdout);
+  assert_eq!(generated["algorithm"], "ES256");
+  assert_eq!(generated["kid"], "ec-1");
+
+  let private_pem = generated["private_key"]["pem"].as_str().unwrap();
+  let key = SigningKey::from_material(
+    Algorithm::ES256,
+    &KeyMaterial::Pem(private_pem.into()),
+  )
+  .unwrap();
+  let public_jwk: Jwk =
+    serde_json::from_value(generated["public_key"]["jwk"].clone()).unwrap();
+  assert_eq!(public_jwk.kid.as_deref(), Some("ec-1"));
+  let verifying_key = VerifyingKey::from_material(
+    Algorithm::ES256,
+    &KeyMaterial::Jwk(Box::new(public_jwk)),
+  )
+  .unwrap();
+  let claims = Claims::with_custom_claims(json!({}), Duration::from_mins(5));
+  let token = key.sign(claims).unwrap();
+  assert!(verifying_key.verify_with(&token, &Default::default()).is_ok());
+
+  let keygen = jwt_easy(&["keygen", "HS384"], "");
+  let secret = json(&keygen.stdout)["secret"].as_str().unwrap().to_string();
+  assert!(SigningKey::from_secret(Algorithm::HS384, secret.as_bytes()).is_ok());
+
+  let keygen = jwt_easy(&["keygen", "none"], "");
+  assert!(!keygen.status.success());
+}
+
+#[test]
+fn jwks_takes_an_algorithm_per_key() {
+  let rsa = SigningKey::generate(Algorithm::RS256, None).unwrap();
+  let ec = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let rsa_path = temp_file("rsa.pem", &rsa.to_pem().unwrap());
+  let ec_path = temp_file("ec.pem", &ec.to_pem().unwrap());
+  let paths = [rsa_path.to_str().unwrap(), ec_path.to_str().unwrap()];
+
+  let jwks = jwt_easy(
+    &[
+      "jwks", "--alg", "RS256", "--alg", "ES256", "--kid", "rsa-1", "--kid",
+      "ec-1", paths[0], paths[1],
+    ],
+    "",
+  );
+  assert!(jwks.status.success());
+  let keys = json(&jwks.stdout);
+  assert_eq!(keys["keys"][0]["kty"], "RSA");
+  assert_eq!(keys["keys"][0]["alg"], "RS256");
+  assert_eq!(keys["keys"][1]["kty"], "EC");
+  assert_eq!(keys["keys"][1]["alg"], "ES256");
+  assert!(keys["keys"][1].get("d").is_none());
+
+  // Con más de un algoritmo tiene que haber uno por clave
+  let jwks = jwt_easy(
+    &["jwks", "--alg", "RS256", "--alg", "ES256", "--alg", "ES256", paths[0]],
+    "",
+  );
+  assert_eq!(json(&jwks.stderr)["code"], "InvalidOptions");
+}
@@ -0,0 +1,11 @@
+use jwt_wasm::JwtOptions;
+use serde_json::{json, Value};
+
+pub const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+// Opciones con `SECRET` y cinco minutos de duración, más los campos de `extra`
+pub fn options(extra: Value) -> JwtOptions {
+  let mut options = json!({ "secret": SECRET, "expires_in": "5m" });
+  options.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+  serde_json::from_value(options).unwrap()
+}
@@ -0,0 +1,71 @@
+use jwt_wasm::core::{self, secret_signing_key, secret_verifying_key};
+use jwt_wasm::{
+  Algorithm, JwtError, JwtOptions, SigningKey, VerifyOptions, VerifyingKey,
+};
+use serde::{Deserialize, Serialize};
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Session {
+  user_id: u64,
+  roles: Vec<String>,
+}
+
+fn session() -> Session {
+  Session { user_id: 42, roles: vec!["admin".to_string()] }
+}
+
+fn options() -> JwtOptions {
+  let mut options = JwtOptions::new(SECRET.to_string(), 5 * 60 * 1000);
+  options.set_issuer("https://auth.example.com".to_string());
+  options
+}
+
+#[test]
+fn typed_payload_round_trip() {
+  let options = options();
+  let key = secret_signing_key(&options).unwrap();
+  let token = core::sign(&session(), &key, &options).unwrap();
+
+  let key =
+    secret_verifying_key(Algorithm::HS256, SECRET.into(), &Default::default())
+      .unwrap();
+  let verify_options: VerifyOptions =
+    serde_json::from_str(r#"{"allowed_issuers":["https://auth.example.com"]}"#)
+      .unwrap();
+  let decoded: Session = core::verify(&token, &key, &verify_options).unwrap();
+  assert_eq!(decoded, session());
+
+  let verified = core::verify_full(&token, &key, &verify_options).unwrap();
+  assert_eq!(verified.header.alg, "HS256");
+}
+
+#[test]
+fn private_key_round_trip() {
+  let signing_key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let token = core::sign(&session(), &signing_key, &options()).unwrap();
+
+  let pem = signing_key.verifying_key().to_pem().unwrap();
+  let key = VerifyingKey::from_material(
+    Algorithm::ES256,
+    &jwt_wasm::KeyMaterial::Pem(pem.into()),
+  )
+  .unwrap();
+  let decoded: Session =
+    core::verify(&token, &key, &VerifyOptions::default()).unwrap();
+  assert_eq!(decoded, session());
+}
+
+#[test]
+fn payload_of_another_type_is_rejected() {
+  let options = options();
+  let key = secret_signing_key(&options).unwrap();
+  let token = core::sign(&session(), &key, &options).unwrap();
+
+  let key =
+    secret_verifying_key(Algorithm::HS256, SECRET.into(), &Default::default())
+      .unwrap();
+  let result = core::verify::<Vec<String>>(&token, &key, &Default::default());
+  assert!(matches!(result, Err(JwtError::PayloadParse(_))));
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{options, SECRET};
+use jwt_wasm::core::{self, secret_signing_key, secret_verifying_key};
+use jwt_wasm::{
+  Algorithm, CriticalHeaders, JwtError, SigningKey, TokenHeader, VerifyOptions,
+};
+use serde_json::json;
+
+fn sign(extra: serde_json::Value) -> Result<String, JwtError> {
+  let options = options(extra);
+  let key = secret_signing_key(&options)?;
+  core::sign(&json!({}), &key, &options)
+}
+
+fn verify(token: &str, options: &VerifyOptions) -> Result<(), JwtError> {
+  let key =
+    secret_verifying_key(Algorithm::HS256, SECRET.into(), &Default::default())?;
+  core::verify_full(token, &key, options).map(|_| ())
+}
+
+fn tenant_token() -> String {
+  sign(json!({ "header": { "tenant": "acme" }, "crit": ["tenant"] })).unwrap()
+}
+
+#[test]
+fn unknown_critical_parameters_fail_closed() {
+  let token = tenant_token();
+  let result = verify(&token, &VerifyOptions::default());
+  assert_eq!(
+    result,
+    Err(JwtError::UnsupportedCritical { parameter: "tenant".to_string() })
+  );
+
+  // Tampoco pasa por la verificación con la clave directamente
+  let key = SigningKey::from_secret(Algorithm::HS256, SECRET.as_bytes())
+    .unwrap()
+    .verifying_key();
+  assert!(matches!(
+    key.verify_with(&token, &VerifyOptions::default()),
+    Err(JwtError::UnsupportedCritical { .. })
+  ));
+}
+
+#[test]
+fn registered_handlers_decide() {
+  let token = tenant_token();
+  let mut options = VerifyOptions::default();
+  options
+    .critical_headers
+    .register("tenant", |value, header: &TokenHeader| {
+      assert_eq!(header.crit.as_deref(), Some(&["tenant".to_string()][..]));
+      match value.as_str() {
+        Some("acme") => Ok(()),
+        _ => Err(JwtError::CriticalRejected { parameter: "tenant".into() }),
+      }
+    })
+    .unwrap();
+  assert!(verify(&token, &options).is_ok());
+
+  let token =
+    sign(json!({ "header": { "tenant": "other" }, "crit": ["tenant"] }))
+      .unwrap();
+  assert!(matches!(
+    verify(&token, &options),
+    Err(JwtError::CriticalRejected { .. })
+  ));
+
+  // Sin `crit`, el parámetro es uno más y no se consulta al manejador
+  let token = sign(json!({ "header": { "tenant": "other" } })).unwrap();
+  assert!(verify(&token, &options).is_ok());
+}
+
+#[test]
+fn malformed_crit_is_rejected() {
+  let mut critical_headers = CriticalHeaders::new();
+  assert!(matches!(
+    critical_headers.accept("kid"),
+    Err(JwtError::InvalidOptions(_))
+  ));
+
+  for options in [
+    json!({ "crit": ["tenant"] }),
+    json!({ "crit": [] }),
+    json!({ "header": { "tenant": "acme" }, "crit": ["alg"] }),
+    json!({ "header": { "tenant": "acme" }, "crit": ["tenant", "tenant"] }),
+  ] {
+    assert!(matches!(sign(options), Err(JwtError::InvalidOptions(_))));
+  }
+}
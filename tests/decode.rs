@@ -0,0 +1,62 @@
+use jwt_wasm::core::{self, secret_signing_key};
+use jwt_wasm::{JwtError, JwtOptions, UnverifiedToken};
+use serde_json::json;
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+fn token() -> String {
+  let options: JwtOptions = serde_json::from_value(json!({
+    "secret": SECRET,
+    "expires_in": "5m",
+    "kid": "hmac-1",
+    "cty": "example",
+    "issuer": "https://auth.example.com",
+  }))
+  .unwrap();
+  let key = secret_signing_key(&options).unwrap();
+  core::sign(&json!({ "name": "Ada" }), &key, &options).unwrap()
+}
+
+#[test]
+fn header_and_claims_are_decoded() {
+  let decoded = UnverifiedToken::decode(&token()).unwrap();
+  assert!(!decoded.verified);
+  assert_eq!(decoded.header.alg, "HS256");
+  assert_eq!(decoded.header.typ.as_deref(), Some("JWT"));
+  assert_eq!(decoded.header.kid.as_deref(), Some("hmac-1"));
+  assert_eq!(decoded.header.cty.as_deref(), Some("example"));
+  assert_eq!(decoded.claims.iss.as_deref(), Some("https://auth.example.com"));
+  assert!(decoded.claims.exp.is_some());
+  assert_eq!(decoded.payload, json!({ "name": "Ada" }));
+
+  let json = serde_json::to_value(&decoded).unwrap();
+  assert_eq!(json["verified"], false);
+}
+
+#[test]
+fn the_signature_is_not_checked() {
+  let token = token();
+  let (signing_input, _) = token.rsplit_once('.').unwrap();
+  let forged = format!("{signing_input}.c2lnbmF0dXJl");
+  let decoded = UnverifiedToken::decode(&forged).unwrap();
+  assert!(!decoded.verified);
+  assert_eq!(decoded.payload["name"], "Ada");
+}
+
+#[test]
+fn malformed_tokens_are_rejected() {
+  let token = token();
+  let mut parts = token.split('.');
+  let (header, claims) = (parts.next().unwrap(), parts.next().unwrap());
+  for malformed in [
+    String::new(),
+    format!("{header}.{claims}"),
+    format!("{header}.{claims}.sig.extra"),
+    format!("{header}.not*base64.sig"),
+    format!("{header}.bm90IGpzb24.sig"),
+    format!("e30.{claims}.sig"),
+  ] {
+    let result = UnverifiedToken::decode(&malformed);
+    assert!(matches!(result, Err(JwtError::Malformed(_))), "{malformed}");
+  }
+}
@@ -0,0 +1,98 @@
+use jwt_wasm::core::{self, secret_signing_key, secret_verifying_key};
+use jwt_wasm::{
+  Algorithm, Jwk, JwtError, JwtOptions, KeyMaterial, SigningKey, TokenHeader,
+  VerifyOptions, VerifyingKey,
+};
+use serde_json::json;
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+const BODY: &[u8] = br#"{"event":"invoice.paid","id":"evt_1"}"#;
+
+fn hmac_key() -> VerifyingKey {
+  secret_verifying_key(Algorithm::HS256, SECRET.into(), &Default::default())
+    .unwrap()
+}
+
+// Clave HMAC de los ejemplos de la RFC 7797 (apéndice A)
+fn rfc7797_key() -> VerifyingKey {
+  let jwk = Jwk::from_json(
+    r#"{"kty":"oct","k":"AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow"}"#,
+  )
+  .unwrap();
+  VerifyingKey::from_material(
+    Algorithm::HS256,
+    &KeyMaterial::Jwk(Box::new(jwk)),
+  )
+  .unwrap()
+}
+
+#[test]
+fn unencoded_detached_payload_round_trip() {
+  let options: JwtOptions = serde_json::from_value(json!({
+    "secret": SECRET,
+    "expires_in": "5m",
+    "kid": "webhooks-1",
+  }))
+  .unwrap();
+  let key = secret_signing_key(&options).unwrap();
+  let token = core::sign_detached(BODY, &key, &options).unwrap();
+  let parts: Vec<&str> = token.split('.').collect();
+  assert_eq!(parts.len(), 3);
+  assert!(parts[1].is_empty());
+
+  let options = VerifyOptions::default();
+  let header =
+    core::verify_detached(&token, BODY, &hmac_key(), &options).unwrap();
+  assert_eq!(header.extra["b64"], false);
+  assert_eq!(header.crit, Some(vec!["b64".to_string()]));
+  assert_eq!(header.kid.as_deref(), Some("webhooks-1"));
+  assert_eq!(header.typ, None);
+
+  let tampered = br#"{"event":"invoice.paid","id":"evt_2"}"#;
+  let result = core::verify_detached(&token, tampered, &hmac_key(), &options);
+  assert_eq!(result, Err(JwtError::InvalidSignature));
+
+  // Un JWS separado nunca pasa por un JWT
+  assert!(hmac_key().verify_with(&token, &options).is_err());
+}
+
+#[test]
+fn asymmetric_keys_sign_detached_payloads() {
+  let key = SigningKey::generate(Algorithm::EdDSA, None).unwrap();
+  let header =
+    TokenHeader { typ: Some("JOSE".to_string()), ..Default::default() };
+  let token = key.sign_detached(BODY, header).unwrap();
+
+  let options: VerifyOptions =
+    serde_json::from_value(json!({ "required_type": "JOSE" })).unwrap();
+  let header = key.verifying_key().verify_detached(&token, BODY, &options);
+  assert_eq!(header.unwrap().alg, "EdDSA");
+}
+
+#[test]
+fn rfc7797_examples_verify() {
+  let options = VerifyOptions::default();
+
+  // A.4: `b64: false`
+  let token = "eyJhbGciOiJIUzI1NiIsImI2NCI6ZmFsc2UsImNyaXQiOlsiYjY0Il19..A5dxf2s96_n5FLueVuW1Z_vh161FwXZC4YLPff6dmDY";
+  assert!(rfc7797_key().verify_detached(token, b"$.02", &options).is_ok());
+
+  // A.1 sin el payload: con `b64` por defecto se firma codificado
+  let token =
+    "eyJhbGciOiJIUzI1NiJ9..5mvfOroL-g7HyqJoozehmsaqmvTYGEq5jTI1gVvoEoQ";
+  assert!(rfc7797_key().verify_detached(token, b"$.02", &options).is_ok());
+}
+
+#[test]
+fn attached_payloads_are_not_detached() {
+  let options = JwtOptions::new(SECRET.to_string(), 60_000);
+  let key = secret_signing_key(&options).unwrap();
+  let token = core::sign(&json!({}), &key, &options).unwrap();
+  let result = core::verify_detached(
+    &token,
+    b"{}",
+    &hmac_key(),
+    &VerifyOptions::default(),
+  );
+  assert!(matches!(result, Err(JwtError::Malformed(_))));
+}
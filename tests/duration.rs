@@ -0,0 +1,115 @@
+use jwt_wasm::{JwtError, JwtOptions};
+use serde_json::{json, Value};
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+fn options(expires_in: Value) -> Result<JwtOptions, serde_json::Error> {
+  serde_json::from_value(json!({ "secret": SECRET, "expires_in": expires_in }))
+}
+
+fn millis(expires_in: Value) -> u64 {
+  options(expires_in).unwrap().get_milliseconds()
+}
+
+#[test]
+fn durations_accept_every_format() {
+  assert_eq!(millis(json!(1500)), 1500);
+  assert_eq!(millis(json!("120")), 120);
+  assert_eq!(millis(json!("250ms")), 250);
+  assert_eq!(millis(json!("90s")), 90_000);
+  assert_eq!(millis(json!("15m")), 15 * 60 * 1000);
+  assert_eq!(millis(json!("2 hours")), 2 * 60 * 60 * 1000);
+  assert_eq!(millis(json!("1.5h")), 90 * 60 * 1000);
+  assert_eq!(millis(json!("7d")), 7 * 24 * 60 * 60 * 1000);
+  assert_eq!(millis(json!("1w")), 7 * 24 * 60 * 60 * 1000);
+  assert_eq!(millis(json!("1y")), 31_557_600_000);
+  assert_eq!(millis(json!({ "seconds": 300 })), 300_000);
+  assert_eq!(millis(json!({ "milliseconds": 42 })), 42);
+}
+
+#[test]
+fn sub_hour_expirations_keep_their_precision() {
+  for (expires_in, seconds) in [("5m", 300), ("90m", 5400), ("1500ms", 1)] {
+    let claims = options(json!(expires_in)).unwrap().claims(json!({})).unwrap();
+    let issued_at = claims.issued_at.unwrap().as_secs();
+    let expires_at = claims.expires_at.unwrap().as_secs();
+    assert!(expires_at - issued_at >= seconds, "{expires_in}");
+    assert!(expires_at - issued_at <= seconds + 1, "{expires_in}");
+  }
+}
+
+#[test]
+fn zero_is_an_explicit_error() {
+  for expires_in in [json!(0), json!("0"), json!("0s"), json!({ "seconds": 0 })]
+  {
+    assert!(options(expires_in).is_err());
+  }
+
+  let mut options = JwtOptions::new(SECRET.to_string(), 60_000);
+  assert!(matches!(
+    options.set_expires_in("0m"),
+    Err(JwtError::InvalidOptions(_))
+  ));
+  assert_eq!(options.get_milliseconds(), 60_000);
+
+  let options = JwtOptions::new(SECRET.to_string(), 0);
+  assert!(matches!(
+    options.claims(json!({})),
+    Err(JwtError::InvalidOptions(_))
+  ));
+}
+
+#[test]
+fn invalid_durations_are_rejected() {
+  for expires_in in [
+    json!(-5),
+    json!("-5m"),
+    json!("5 fortnights"),
+    json!("m"),
+    json!({ "minutes": 5 }),
+    json!({ "seconds": 1, "milliseconds": 1 }),
+  ] {
+    assert!(options(expires_in.clone()).is_err(), "{expires_in}");
+  }
+
+  let mut options = JwtOptions::new(SECRET.to_string(), 60_000);
+  assert!(matches!(
+    options.set_expires_in("soon"),
+    Err(JwtError::InvalidOptions(_))
+  ));
+}
+
+#[test]
+fn overflowing_durations_are_rejected() {
+  for expires_in in [
+    json!("600000000y"),
+    json!("99999999999999999999999"),
+    json!({ "seconds": 1e300 }),
+  ] {
+    assert!(options(expires_in.clone()).is_err(), "{expires_in}");
+  }
+  let mut options = JwtOptions::new(SECRET.to_string(), 60_000);
+  assert!(matches!(
+    options.set_expires_in("600000000y"),
+    Err(JwtError::InvalidOptions(_))
+  ));
+
+  // Cabe en milisegundos, pero la expiración ya no se puede representar
+  for expires_in in [json!("500000000y"), json!(u64::MAX)] {
+    let options = self::options(expires_in).unwrap();
+    assert!(matches!(
+      options.claims(json!({})),
+      Err(JwtError::InvalidOptions(_))
+    ));
+  }
+  let options: JwtOptions = serde_json::from_value(json!({
+    "secret": SECRET,
+    "expires_in": "5m",
+    "not_before": "500000000y",
+  }))
+  .unwrap();
+  assert!(matches!(
+    options.claims(json!({})),
+    Err(JwtError::InvalidOptions(_))
+  ));
+}
@@ -0,0 +1,164 @@
+use jwt_simple::prelude::*;
+use jwt_wasm::core;
+use jwt_wasm::{
+  Algorithm, JwtError, JwtOptions, KeyMaterial, SigningKey, VerifyOptions,
+  VerifyingKey,
+};
+use p256::pkcs8::LineEnding;
+use serde_json::{json, Value};
+
+const CURVES: [Algorithm; 3] =
+  [Algorithm::ES256, Algorithm::ES384, Algorithm::ES256K];
+
+// Las claves de una curva en todos los formatos que se aceptan
+struct Materials {
+  private: Vec<KeyMaterial>,
+  public: Vec<KeyMaterial>,
+}
+
+fn materials(algorithm: Algorithm) -> Materials {
+  let key = SigningKey::generate(algorithm, None).unwrap();
+  let jwk = key.to_jwk().unwrap();
+  let scalar = b64_decode(jwk.d.as_ref().unwrap().expose());
+  let x = b64_decode(jwk.x.as_ref().unwrap());
+  let y = b64_decode(jwk.y.as_ref().unwrap());
+  let uncompressed = [&[4], x.as_slice(), y.as_slice()].concat();
+  let compressed = [&[2 + (y[y.len() - 1] & 1)], x.as_slice()].concat();
+
+  let private_pem = key.to_pem().unwrap();
+  let public_pem = key.verifying_key().to_pem().unwrap();
+  Materials {
+    private: vec![
+      der(pem_body(&private_pem)),
+      pem(private_pem),
+      der(scalar),
+      KeyMaterial::Jwk(Box::new(jwk.clone())),
+    ],
+    public: vec![
+      der(pem_body(&public_pem)),
+      pem(public_pem),
+      der(uncompressed),
+      der(compressed),
+      KeyMaterial::Jwk(Box::new(jwk.to_public().unwrap())),
+    ],
+  }
+}
+
+fn pem(pem: String) -> KeyMaterial {
+  KeyMaterial::Pem(pem.into())
+}
+
+fn der(der: Vec<u8>) -> KeyMaterial {
+  KeyMaterial::Der(der.into())
+}
+
+fn b64_decode(value: &str) -> Vec<u8> {
+  Base64UrlSafeNoPadding::decode_to_vec(value, None).unwrap()
+}
+
+// DER de un bloque PEM
+fn pem_body(pem: &str) -> Vec<u8> {
+  let body: String =
+    pem.lines().filter(|line| !line.starts_with("-----")).collect();
+  Base64::decode_to_vec(body, None).unwrap()
+}
+
+fn sign(algorithm: Algorithm, material: &KeyMaterial) -> String {
+  let options = JwtOptions::new(String::new(), 5 * 60 * 1000);
+  let key = SigningKey::from_material(algorithm, material).unwrap();
+  core::sign(&json!({ "curve": algorithm.as_str() }), &key, &options).unwrap()
+}
+
+fn verify(
+  token: &str,
+  algorithm: Algorithm,
+  material: &KeyMaterial,
+) -> Result<Value, JwtError> {
+  let key = VerifyingKey::from_material(algorithm, material)?;
+  core::verify(token, &key, &VerifyOptions::default())
+}
+
+#[test]
+fn ec_keys_load_from_every_format() {
+  for algorithm in CURVES {
+    let materials = materials(algorithm);
+    for private in &materials.private {
+      let token = sign(algorithm, private);
+      for public in &materials.public {
+        let payload = verify(&token, algorithm, public).unwrap();
+        assert_eq!(payload["curve"], algorithm.as_str());
+      }
+    }
+  }
+}
+
+#[test]
+fn sec1_private_keys_are_accepted() {
+  let key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let jwk = key.to_jwk().unwrap();
+  let scalar = b64_decode(jwk.d.as_ref().unwrap().expose());
+  let secret_key = p256::SecretKey::from_slice(&scalar).unwrap();
+  let sec1_pem = secret_key.to_sec1_pem(LineEnding::LF).unwrap();
+  let sec1_der = secret_key.to_sec1_der().unwrap();
+
+  let public = pem(key.verifying_key().to_pem().unwrap());
+  for private in [pem(sec1_pem.to_string()), der(sec1_der.to_vec())] {
+    let token = sign(Algorithm::ES256, &private);
+    assert!(verify(&token, Algorithm::ES256, &public).is_ok());
+  }
+}
+
+#[test]
+fn signatures_use_the_jose_encoding() {
+  for (algorithm, length) in
+    [(Algorithm::ES256, 64), (Algorithm::ES384, 96), (Algorithm::ES256K, 64)]
+  {
+    let materials = materials(algorithm);
+    let token = sign(algorithm, &materials.private[0]);
+    let signature = b64_decode(token.rsplit_once('.').unwrap().1);
+    // r || s con longitud fija, no una secuencia ASN.1 (que empieza por 0x30)
+    assert_eq!(signature.len(), length, "{algorithm}");
+  }
+}
+
+#[test]
+fn jwt_simple_tokens_verify() {
+  let key_pair = ES256KeyPair::generate();
+  let claims =
+    Claims::with_custom_claims(json!({ "n": 1 }), Duration::from_mins(5));
+  let token = key_pair.sign(claims).unwrap();
+  let public = der(key_pair.public_key().public_key().to_bytes_uncompressed());
+  assert_eq!(verify(&token, Algorithm::ES256, &public).unwrap()["n"], 1);
+}
+
+#[test]
+fn curves_are_not_interchangeable() {
+  let p256 = materials(Algorithm::ES256);
+  let secp256k1 = materials(Algorithm::ES256K);
+  let token = sign(Algorithm::ES256, &p256.private[0]);
+  assert_eq!(
+    verify(&token, Algorithm::ES256K, &secp256k1.public[0]),
+    Err(JwtError::AlgorithmMismatch)
+  );
+
+  // Un punto P-256 no es una clave válida de secp256k1
+  let result = VerifyingKey::from_material(Algorithm::ES256K, &p256.public[2]);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn scalars_of_the_wrong_size_are_rejected() {
+  let p256 = materials(Algorithm::ES256);
+  let p384 = materials(Algorithm::ES384);
+  for algorithm in CURVES {
+    // Un escalar de una curva con otro tamaño
+    let other = match algorithm {
+      Algorithm::ES384 => &p256.private[2],
+      _ => &p384.private[2],
+    };
+    for material in [other, &p256.public[2], &der(vec![1, 2, 3])] {
+      let result = SigningKey::from_material(algorithm, material);
+      assert!(matches!(result, Err(JwtError::InvalidKey(_))), "{algorithm}");
+    }
+  }
+}
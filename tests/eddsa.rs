@@ -0,0 +1,88 @@
+use jwt_wasm::core;
+use jwt_wasm::{
+  Algorithm, Jwk, JwtError, JwtOptions, KeyMaterial, SigningKey, VerifyOptions,
+  VerifyingKey,
+};
+use serde_json::{json, Value};
+
+// Clave de los ejemplos de la RFC 8037 (apéndice A.1)
+const PRIVATE_JWK: &str = r#"{"kty":"OKP","crv":"Ed25519","d":"nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A","x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#;
+const SEED: [u8; 32] = [
+  0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92,
+  0xec, 0x2c, 0xc4, 0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19, 0x70, 0x3b,
+  0xac, 0x03, 0x1c, 0xae, 0x7f, 0x60,
+];
+const PUBLIC_X: &str = "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo";
+
+fn jwk() -> Jwk {
+  Jwk::from_json(PRIVATE_JWK).unwrap()
+}
+
+fn sign(key: &SigningKey) -> String {
+  let options = JwtOptions::new(String::new(), 5 * 60 * 1000);
+  core::sign(&json!({ "n": 1 }), key, &options).unwrap()
+}
+
+fn verify(token: &str, key: &VerifyingKey) -> Result<Value, JwtError> {
+  core::verify(token, key, &VerifyOptions::default())
+}
+
+#[test]
+fn seed_pem_and_jwk_load_the_same_key() {
+  let from_seed = SigningKey::from_ed25519_seed(&SEED).unwrap();
+  let pem = KeyMaterial::Pem(from_seed.to_pem().unwrap().into());
+  let keys = [
+    SigningKey::from_material(
+      Algorithm::EdDSA,
+      &KeyMaterial::Der(SEED.to_vec().into()),
+    )
+    .unwrap(),
+    SigningKey::from_material(Algorithm::EdDSA, &pem).unwrap(),
+    SigningKey::from_material(
+      Algorithm::EdDSA,
+      &KeyMaterial::Jwk(jwk().into()),
+    )
+    .unwrap(),
+    from_seed,
+  ];
+  for key in &keys {
+    assert_eq!(key.algorithm(), Algorithm::EdDSA);
+    let public = key.verifying_key().to_jwk().unwrap();
+    assert_eq!(public.x.as_deref(), Some(PUBLIC_X));
+  }
+
+  let public = VerifyingKey::from_material(
+    Algorithm::EdDSA,
+    &KeyMaterial::Jwk(jwk().to_public().unwrap().into()),
+  )
+  .unwrap();
+  for key in &keys {
+    assert_eq!(verify(&sign(key), &public).unwrap()["n"], 1);
+  }
+}
+
+#[test]
+fn rfc8037_signature_verifies() {
+  // A.4, con el payload separado: se firma codificado en base64url
+  let token = "eyJhbGciOiJFZERTQSJ9..hgyY0il_MGCjP0JzlnLWG1PPOt7-09PGcvMg3AIbQR6dWbhijcNR4ki4iylGjg5BhVsPt9g7sVvpAr_MuM0KAg";
+  let key = SigningKey::from_ed25519_seed(&SEED).unwrap().verifying_key();
+  let payload = b"Example of Ed25519 signing";
+  let options = VerifyOptions::default();
+  assert!(key.verify_detached(token, payload, &options).is_ok());
+  assert_eq!(
+    key.verify_detached(token, b"Example of Ed25519 signinG", &options),
+    Err(JwtError::InvalidSignature)
+  );
+}
+
+#[test]
+fn invalid_ed25519_keys_are_rejected() {
+  let result = SigningKey::from_ed25519_seed(&SEED[..31]);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+
+  // Una clave de otra curva no sirve para EdDSA
+  let es256 = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let material = KeyMaterial::Jwk(es256.to_jwk().unwrap().into());
+  let result = SigningKey::from_material(Algorithm::EdDSA, &material);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+}
@@ -0,0 +1,96 @@
+use jwt_wasm::core::{self, secret_signing_key, secret_verifying_key};
+use jwt_wasm::{Algorithm, JwtError, JwtOptions, VerifyOptions};
+use serde_json::json;
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+#[test]
+fn codes_are_the_variant_names() {
+  let errors = [
+    (JwtError::Expired { expired_at: None }, "Expired"),
+    (JwtError::NotYetValid { not_before: None }, "NotYetValid"),
+    (JwtError::TooOld, "TooOld"),
+    (JwtError::InvalidSignature, "InvalidSignature"),
+    (JwtError::DecryptionFailed, "DecryptionFailed"),
+    (JwtError::Malformed(String::new()), "Malformed"),
+    (JwtError::AlgorithmMismatch, "AlgorithmMismatch"),
+    (JwtError::UnsupportedAlgorithm(String::new()), "UnsupportedAlgorithm"),
+    (JwtError::KeyIdMismatch, "KeyIdMismatch"),
+    (JwtError::KeyRetired { retired_at: None }, "KeyRetired"),
+    (JwtError::AudienceMismatch, "AudienceMismatch"),
+    (JwtError::IssuerMismatch, "IssuerMismatch"),
+    (JwtError::SubjectMismatch, "SubjectMismatch"),
+    (JwtError::JwtIdMismatch, "JwtIdMismatch"),
+    (JwtError::TypeMismatch, "TypeMismatch"),
+    (
+      JwtError::UnsupportedCritical { parameter: String::new() },
+      "UnsupportedCritical",
+    ),
+    (
+      JwtError::CriticalRejected { parameter: String::new() },
+      "CriticalRejected",
+    ),
+    (JwtError::InvalidKey(String::new()), "InvalidKey"),
+    (JwtError::InvalidOptions(String::new()), "InvalidOptions"),
+    (JwtError::PayloadParse(String::new()), "PayloadParse"),
+    (JwtError::Internal(String::new()), "Internal"),
+  ];
+  for (error, code) in errors {
+    assert_eq!(error.code(), code);
+  }
+}
+
+#[test]
+fn details_carry_the_relevant_values() {
+  let details = |error: JwtError| error.details();
+  assert_eq!(
+    details(JwtError::Expired { expired_at: Some(1_700_000_000) }),
+    Some(json!({ "expired_at": 1_700_000_000 }))
+  );
+  assert_eq!(
+    details(JwtError::NotYetValid { not_before: Some(1_800_000_000) }),
+    Some(json!({ "not_before": 1_800_000_000 }))
+  );
+  assert_eq!(
+    details(JwtError::KeyRetired { retired_at: Some(1_600_000_000) }),
+    Some(json!({ "retired_at": 1_600_000_000 }))
+  );
+  assert_eq!(
+    details(JwtError::UnsupportedCritical { parameter: "exp".to_string() }),
+    Some(json!({ "parameter": "exp" }))
+  );
+  assert_eq!(details(JwtError::Expired { expired_at: None }), None);
+  assert_eq!(details(JwtError::InvalidSignature), None);
+}
+
+#[test]
+fn messages_are_readable() {
+  assert_eq!(
+    JwtError::Expired { expired_at: None }.to_string(),
+    "Token has expired"
+  );
+  assert_eq!(
+    JwtError::Malformed("bad base64".to_string()).to_string(),
+    "Malformed token: bad base64"
+  );
+}
+
+#[test]
+fn verification_failures_have_distinct_codes() {
+  let options = JwtOptions::new(SECRET.to_string(), 60_000);
+  let key = secret_signing_key(&options).unwrap();
+  let token = core::sign(&json!({}), &key, &options).unwrap();
+  let verify = |token: &str| {
+    let key = secret_verifying_key(
+      Algorithm::HS256,
+      "fedcba9876543210fedcba9876543210".into(),
+      &Default::default(),
+    )
+    .unwrap();
+    core::verify_full(token, &key, &VerifyOptions::default())
+  };
+
+  // Una firma falsificada no se confunde con un token mal formado
+  assert_eq!(verify(&token).unwrap_err().code(), "InvalidSignature");
+  assert_eq!(verify("not-a-token").unwrap_err().code(), "Malformed");
+}
@@ -0,0 +1,207 @@
+mod common;
+
+use common::{options, SECRET};
+use jwt_simple::prelude::*;
+use jwt_wasm::core::{
+  self, allowed_token_algorithm, secret_signing_key, secret_verifying_key,
+};
+use jwt_wasm::{
+  Algorithm, JwtError, JwtOptions, KeyMaterial, KeyRing, SigningKey,
+  UnverifiedToken, VerifyOptions, VerifyingKey,
+};
+use serde_json::{json, Value};
+
+fn verify_options(options: Value) -> VerifyOptions {
+  serde_json::from_value(options).unwrap()
+}
+
+fn sign(options: &JwtOptions) -> Result<String, JwtError> {
+  let key = secret_signing_key(options)?;
+  core::sign(&json!({ "scope": "read" }), &key, options)
+}
+
+fn verify_full(
+  token: &str,
+  options: &VerifyOptions,
+) -> Result<jwt_wasm::VerifiedToken, JwtError> {
+  let key =
+    secret_verifying_key(Algorithm::HS256, SECRET.into(), &Default::default())?;
+  core::verify_full(token, &key, options)
+}
+
+// Verifica el token con `jwt-simple` en lugar de con nuestro `jws::decode`
+fn jwt_simple_verify(
+  key: &VerifyingKey,
+  token: &str,
+) -> Result<JWTClaims<Value>, jwt_simple::Error> {
+  match key {
+    VerifyingKey::HS256(key) => key.verify_token(token, None),
+    VerifyingKey::HS384(key) => key.verify_token(token, None),
+    VerifyingKey::HS512(key) => key.verify_token(token, None),
+    VerifyingKey::RS256(key) => key.verify_token(token, None),
+    VerifyingKey::RS384(key) => key.verify_token(token, None),
+    VerifyingKey::RS512(key) => key.verify_token(token, None),
+    VerifyingKey::PS256(key) => key.verify_token(token, None),
+    VerifyingKey::PS384(key) => key.verify_token(token, None),
+    VerifyingKey::PS512(key) => key.verify_token(token, None),
+    VerifyingKey::ES256(key) => key.verify_token(token, None),
+    VerifyingKey::ES384(key) => key.verify_token(token, None),
+    VerifyingKey::ES256K(key) => key.verify_token(token, None),
+    VerifyingKey::EdDSA(key) => key.verify_token(token, None),
+  }
+}
+
+#[test]
+fn access_token_type_is_required() {
+  let token = sign(&options(json!({ "typ": "at+jwt" }))).unwrap();
+  let at_jwt = verify_options(json!({ "required_type": "at+jwt" }));
+  let verified = verify_full(&token, &at_jwt).unwrap();
+  assert_eq!(verified.header.typ.as_deref(), Some("at+jwt"));
+
+  // El prefijo `application/` y las mayúsculas no cuentan
+  let application =
+    verify_options(json!({ "required_typ": "application/AT+JWT" }));
+  assert!(verify_full(&token, &application).is_ok());
+
+  // Un token genérico no pasa por un access token
+  let token = sign(&options(json!({}))).unwrap();
+  let result = verify_full(&token, &at_jwt);
+  assert!(matches!(result, Err(JwtError::TypeMismatch)));
+}
+
+#[test]
+fn any_type_is_accepted_unless_one_is_required() {
+  let token = sign(&options(json!({ "typ": "secevent+jwt" }))).unwrap();
+  assert!(verify_full(&token, &VerifyOptions::default()).is_ok());
+
+  let token = sign(&options(json!({ "typ": "dpop" }))).unwrap();
+  assert!(verify_full(&token, &VerifyOptions::default()).is_ok());
+  let options = VerifyOptions {
+    required_type: Some("at+jwt".to_string()),
+    ..Default::default()
+  };
+  let result = verify_full(&token, &options);
+  assert!(matches!(result, Err(JwtError::TypeMismatch)));
+}
+
+#[test]
+fn custom_header_parameters_round_trip() {
+  let options = options(json!({
+    "kid": "hmac-1",
+    "cty": "example",
+    "header": {
+      "jku": "https://auth.example.com/jwks.json",
+      "x5t#S256": "thumbprint",
+      "tenant": "acme",
+    },
+  }));
+  let token = sign(&options).unwrap();
+
+  let header = verify_full(&token, &VerifyOptions::default()).unwrap().header;
+  assert_eq!(header.typ.as_deref(), Some("JWT"));
+  assert_eq!(header.kid.as_deref(), Some("hmac-1"));
+  assert_eq!(header.cty.as_deref(), Some("example"));
+  assert_eq!(header.jku.as_deref(), Some("https://auth.example.com/jwks.json"));
+  assert_eq!(header.x5t_s256.as_deref(), Some("thumbprint"));
+  assert_eq!(header.extra["tenant"], "acme");
+
+  let unverified = UnverifiedToken::decode(&token).unwrap();
+  assert_eq!(unverified.header, header);
+}
+
+#[test]
+fn headers_can_carry_a_jwk_object() {
+  // Como una prueba DPoP (RFC 9449), que lleva su clave pública en `jwk`
+  let key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let jwk = key.verifying_key().to_jwk().unwrap();
+  let options = options(json!({
+    "typ": "dpop+jwt",
+    "header": { "jwk": serde_json::to_value(&jwk).unwrap() },
+  }));
+  let token = core::sign(&json!({}), &key, &options).unwrap();
+
+  let verify_options = VerifyOptions::default();
+  let algorithm = allowed_token_algorithm(&token, &verify_options).unwrap();
+  assert_eq!(algorithm, Algorithm::ES256);
+  let verified =
+    core::verify_full(&token, &key.verifying_key(), &verify_options).unwrap();
+  assert_eq!(verified.header.typ.as_deref(), Some("dpop+jwt"));
+  assert_eq!(verified.header.extra["jwk"]["kty"], "EC");
+
+  let mut ring = KeyRing::default();
+  ring.add_signing_key("dpop-1", key, None, None).unwrap();
+  let claims = options.claims(json!({})).unwrap();
+  let token = ring.sign_with_header(claims, options.token_header().unwrap());
+  let verified = ring.verify_full(&token.unwrap(), &verify_options).unwrap();
+  assert_eq!(verified.header.kid.as_deref(), Some("dpop-1"));
+  assert_eq!(verified.header.extra["jwk"]["crv"], "P-256");
+}
+
+#[test]
+fn header_cannot_set_the_algorithm() {
+  let options = options(json!({ "header": { "alg": "none" } }));
+  assert!(matches!(sign(&options), Err(JwtError::InvalidOptions(_))));
+}
+
+#[test]
+fn tokens_stay_compatible_with_jwt_simple() {
+  let options = options(json!({ "typ": "at+jwt" }));
+  let mut keys =
+    vec![SigningKey::from_secret(Algorithm::HS512, &[7; 64]).unwrap()];
+  for algorithm in [
+    Algorithm::RS256,
+    Algorithm::PS384,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::ES256K,
+    Algorithm::EdDSA,
+  ] {
+    keys.push(SigningKey::generate(algorithm, None).unwrap());
+  }
+  for key in keys {
+    let algorithm = key.algorithm();
+    let token = core::sign(&json!({ "n": 1 }), &key, &options).unwrap();
+    let claims = jwt_simple_verify(&key.verifying_key(), &token)
+      .unwrap_or_else(|err| panic!("{algorithm}: {err}"));
+    assert_eq!(claims.custom["n"], 1, "{algorithm}");
+  }
+
+  // Y al revés: verificamos los tokens que firma `jwt-simple`
+  let claims =
+    || Claims::with_custom_claims(json!({ "n": 2 }), Duration::from_mins(5));
+  let pem = |algorithm, pem: String| {
+    VerifyingKey::from_material(algorithm, &KeyMaterial::Pem(pem.into()))
+      .unwrap()
+  };
+  let hs512 = HS512Key::from_bytes(&[7; 64]);
+  let ps384 = PS384KeyPair::generate(2048).unwrap();
+  let es256 = ES256KeyPair::generate();
+  let ed25519 = Ed25519KeyPair::generate();
+  let tokens = [
+    (
+      Algorithm::HS512,
+      hs512.authenticate(claims()).unwrap(),
+      VerifyingKey::from_secret(Algorithm::HS512, &[7; 64]).unwrap(),
+    ),
+    (
+      Algorithm::PS384,
+      ps384.sign(claims()).unwrap(),
+      pem(Algorithm::PS384, ps384.public_key().to_pem().unwrap()),
+    ),
+    (
+      Algorithm::ES256,
+      es256.sign(claims()).unwrap(),
+      pem(Algorithm::ES256, es256.public_key().to_pem().unwrap()),
+    ),
+    (
+      Algorithm::EdDSA,
+      ed25519.sign(claims()).unwrap(),
+      pem(Algorithm::EdDSA, ed25519.public_key().to_pem()),
+    ),
+  ];
+  for (algorithm, token, verifying_key) in tokens {
+    let claims =
+      verifying_key.verify_with(&token, &VerifyOptions::default()).unwrap();
+    assert_eq!(claims.custom["n"], 2, "{algorithm}");
+  }
+}
@@ -0,0 +1,74 @@
+use jwt_wasm::core::{self, secret_signing_key, secret_verifying_key};
+use jwt_wasm::{
+  token_algorithm, Algorithm, JwtError, JwtOptions, VerifyOptions,
+};
+use serde_json::{json, Value};
+
+// 64 bytes: sirve para los tres algoritmos HMAC
+const SECRET: &str =
+  "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+fn options(algorithm: &str) -> JwtOptions {
+  serde_json::from_value(json!({
+    "secret": SECRET,
+    "expires_in": "5m",
+    "algorithm": algorithm,
+  }))
+  .unwrap()
+}
+
+fn verify(token: &str, algorithm: Algorithm) -> Result<Value, JwtError> {
+  let key =
+    secret_verifying_key(algorithm, SECRET.into(), &Default::default())?;
+  core::verify(token, &key, &VerifyOptions::default())
+}
+
+#[test]
+fn algorithm_selects_the_hmac_key() {
+  for algorithm in [Algorithm::HS256, Algorithm::HS384, Algorithm::HS512] {
+    let options = options(algorithm.as_str());
+    let key = secret_signing_key(&options).unwrap();
+    assert_eq!(key.algorithm(), algorithm);
+
+    let token = core::sign(&json!({ "n": 1 }), &key, &options).unwrap();
+    assert_eq!(token_algorithm(&token).unwrap(), algorithm);
+    assert_eq!(verify(&token, algorithm).unwrap()["n"], 1, "{algorithm}");
+  }
+}
+
+#[test]
+fn hs256_is_the_default() {
+  let options = JwtOptions::new(SECRET.to_string(), 60_000);
+  assert_eq!(options.get_algorithm(), "HS256");
+  let key = secret_signing_key(&options).unwrap();
+  assert_eq!(key.algorithm(), Algorithm::HS256);
+}
+
+#[test]
+fn verifying_key_must_match_the_token_algorithm() {
+  let options = options("HS512");
+  let key = secret_signing_key(&options).unwrap();
+  let token = core::sign(&json!({}), &key, &options).unwrap();
+
+  for algorithm in [Algorithm::HS256, Algorithm::HS384] {
+    assert_eq!(verify(&token, algorithm), Err(JwtError::AlgorithmMismatch));
+  }
+}
+
+#[test]
+fn unknown_algorithms_are_rejected() {
+  let mut options = JwtOptions::new(SECRET.to_string(), 60_000);
+  assert_eq!(
+    options.set_algorithm("HS1024"),
+    Err(JwtError::UnsupportedAlgorithm("HS1024".to_string()))
+  );
+  options.set_algorithm("HS384").unwrap();
+  assert_eq!(options.get_algorithm(), "HS384");
+
+  let result = serde_json::from_value::<JwtOptions>(json!({
+    "secret": SECRET,
+    "expires_in": 60_000,
+    "algorithm": "hs256",
+  }));
+  assert!(result.is_err());
+}
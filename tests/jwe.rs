@@ -0,0 +1,321 @@
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes128Gcm, Nonce};
+use jwt_simple::prelude::*;
+use jwt_wasm::{
+  Algorithm, ContentEncryption, DecryptOptions, DecryptionKey, EncryptionKey,
+  JweAlgorithm, JweHeader, Jwk, JwtError, KeyMaterial, SigningKey,
+};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+const ALGORITHMS: [JweAlgorithm; 6] = [
+  JweAlgorithm::Dir,
+  JweAlgorithm::A128KW,
+  JweAlgorithm::A256KW,
+  JweAlgorithm::RsaOaep256,
+  JweAlgorithm::EcdhEs,
+  JweAlgorithm::EcdhEsA256KW,
+];
+const ENCRYPTIONS: [ContentEncryption; 3] = [
+  ContentEncryption::A128GCM,
+  ContentEncryption::A256GCM,
+  ContentEncryption::A128CbcHs256,
+];
+const PLAINTEXT: &[u8] = b"The true sign of intelligence is not knowledge";
+
+// Generar claves RSA es lento: todas las pruebas comparten las mismas
+fn rsa_pem(index: usize) -> KeyMaterial {
+  static KEYS: OnceLock<Vec<String>> = OnceLock::new();
+  let keys = KEYS.get_or_init(|| {
+    (0..2)
+      .map(|_| {
+        SigningKey::generate(Algorithm::RS256, None).unwrap().to_pem().unwrap()
+      })
+      .collect()
+  });
+  KeyMaterial::Pem(keys[index].as_str().into())
+}
+
+// Pareja de claves de cifrado y descifrado; `seed` distingue claves
+// distintas del mismo tipo
+fn key_pair(
+  alg: JweAlgorithm,
+  enc: ContentEncryption,
+  seed: u8,
+) -> (EncryptionKey, DecryptionKey) {
+  let secret = |length: usize| vec![seed; length];
+  let asymmetric = |private: KeyMaterial| {
+    let algorithm = match alg {
+      JweAlgorithm::RsaOaep256 => Algorithm::RS256,
+      _ => Algorithm::ES256,
+    };
+    let public = SigningKey::from_material(algorithm, &private)
+      .unwrap()
+      .verifying_key()
+      .to_pem()
+      .unwrap();
+    let public = KeyMaterial::Pem(public.into());
+    (
+      EncryptionKey::from_material(alg, &public).unwrap(),
+      DecryptionKey::from_material(alg, &private).unwrap(),
+    )
+  };
+  match alg {
+    JweAlgorithm::Dir | JweAlgorithm::A128KW | JweAlgorithm::A256KW => {
+      let length = match alg {
+        JweAlgorithm::A128KW => 16,
+        JweAlgorithm::A256KW => 32,
+        _ => enc.key_len(),
+      };
+      (
+        EncryptionKey::from_secret(alg, &secret(length)).unwrap(),
+        DecryptionKey::from_secret(alg, &secret(length)).unwrap(),
+      )
+    }
+    JweAlgorithm::RsaOaep256 => asymmetric(rsa_pem(seed as usize % 2)),
+    _ => {
+      let key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+      asymmetric(KeyMaterial::Pem(key.to_pem().unwrap().into()))
+    }
+  }
+}
+
+fn encode(bytes: &[u8]) -> String {
+  Base64UrlSafeNoPadding::encode_to_string(bytes).unwrap()
+}
+
+fn decode(part: &str) -> Vec<u8> {
+  Base64UrlSafeNoPadding::decode_to_vec(part, None).unwrap()
+}
+
+// Construye a mano un JWE con `A128GCM` y la clave de contenido indicada
+fn seal(header: Value, cek: &[u8], plaintext: &[u8]) -> String {
+  let protected = encode(header.to_string().as_bytes());
+  let iv = [7u8; 12];
+  let mut buffer = plaintext.to_vec();
+  let tag = Aes128Gcm::new_from_slice(cek)
+    .unwrap()
+    .encrypt_in_place_detached(
+      Nonce::from_slice(&iv),
+      protected.as_bytes(),
+      &mut buffer,
+    )
+    .unwrap();
+  format!("{protected}..{}.{}.{}", encode(&iv), encode(&buffer), encode(&tag))
+}
+
+#[test]
+fn every_algorithm_and_encryption_round_trips() {
+  for alg in ALGORITHMS {
+    for enc in ENCRYPTIONS {
+      let (encryption_key, decryption_key) = key_pair(alg, enc, 1);
+      let mut header = JweHeader::new(alg, enc);
+      header.kid = Some("key-1".to_string());
+      let token = encryption_key.encrypt(header, PLAINTEXT).unwrap();
+      assert_eq!(token.split('.').count(), 5);
+
+      let (header, plaintext) = decryption_key.decrypt(&token).unwrap();
+      assert_eq!(plaintext, PLAINTEXT, "{alg} {enc}");
+      assert_eq!((header.alg, header.enc), (alg, enc));
+      assert_eq!(header.kid.as_deref(), Some("key-1"));
+      let uses_ecdh =
+        matches!(alg, JweAlgorithm::EcdhEs | JweAlgorithm::EcdhEsA256KW);
+      assert_eq!(header.epk.is_some(), uses_ecdh);
+    }
+  }
+}
+
+#[test]
+fn tampered_tokens_fail_to_decrypt() {
+  for alg in ALGORITHMS {
+    let enc = ContentEncryption::A256GCM;
+    let (encryption_key, decryption_key) = key_pair(alg, enc, 1);
+    let token =
+      encryption_key.encrypt(JweHeader::new(alg, enc), PLAINTEXT).unwrap();
+    let parts: Vec<&str> = token.split('.').collect();
+
+    // Se cambia el primer byte de cada parte (salvo la cabecera y la clave
+    // cifrada vacía de `dir` y `ECDH-ES`)
+    for index in 1..5 {
+      let mut bytes = decode(parts[index]);
+      let Some(first) = bytes.first_mut() else {
+        continue;
+      };
+      *first ^= 1;
+      let mut tampered = parts.clone();
+      let part = encode(&bytes);
+      tampered[index] = &part;
+      let result = decryption_key.decrypt(&tampered.join("."));
+      assert_eq!(result.unwrap_err(), JwtError::DecryptionFailed, "{alg}");
+    }
+
+    // La cabecera protegida forma parte de los datos autenticados
+    let mut header: Value = serde_json::from_slice(&decode(parts[0])).unwrap();
+    header["kid"] = json!("other");
+    let header = encode(header.to_string().as_bytes());
+    let tampered = [header.as_str(), parts[1], parts[2], parts[3], parts[4]];
+    let result = decryption_key.decrypt(&tampered.join("."));
+    assert_eq!(result.unwrap_err(), JwtError::DecryptionFailed, "{alg}");
+  }
+}
+
+#[test]
+fn the_wrong_key_fails_to_decrypt() {
+  for alg in ALGORITHMS {
+    for enc in ENCRYPTIONS {
+      let (encryption_key, _) = key_pair(alg, enc, 1);
+      let (_, other_key) = key_pair(alg, enc, 2);
+      let token =
+        encryption_key.encrypt(JweHeader::new(alg, enc), PLAINTEXT).unwrap();
+      let result = other_key.decrypt(&token);
+      assert_eq!(result.unwrap_err(), JwtError::DecryptionFailed, "{alg}");
+    }
+  }
+
+  // Una clave de otro algoritmo ni siquiera se usa
+  let enc = ContentEncryption::A256GCM;
+  let (encryption_key, _) = key_pair(JweAlgorithm::A256KW, enc, 1);
+  let (_, dir_key) = key_pair(JweAlgorithm::Dir, enc, 1);
+  let header = JweHeader::new(JweAlgorithm::A256KW, enc);
+  let token = encryption_key.encrypt(header, PLAINTEXT).unwrap();
+  assert_eq!(dir_key.decrypt(&token).unwrap_err(), JwtError::AlgorithmMismatch);
+}
+
+// RFC 7516, apéndice A.3: A128KW con A128CBC-HS256
+#[test]
+fn rfc7516_a3_vector_decrypts() {
+  let token = concat!(
+    "eyJhbGciOiJBMTI4S1ciLCJlbmMiOiJBMTI4Q0JDLUhTMjU2In0.",
+    "6KB707dM9YTIgHtLvtgWQ8mKwboJW3of9locizkDTHzBC2IlrT1oOQ.",
+    "AxY8DCtDaGlsbGljb3RoZQ.",
+    "KDlTtXchhZTGufMYmOYGS4HffxPSUrfmqCHXaI9wOGY.",
+    "U0m_YmjN04DJvceFICbCVQ",
+  );
+  let jwk =
+    Jwk::from_json(r#"{"kty":"oct","k":"GawgguFyGrWKav7AX4VKUg"}"#).unwrap();
+  let key = DecryptionKey::from_material(
+    JweAlgorithm::A128KW,
+    &KeyMaterial::Jwk(Box::new(jwk)),
+  )
+  .unwrap();
+  let (header, plaintext) = key.decrypt(token).unwrap();
+  assert_eq!(header.enc, ContentEncryption::A128CbcHs256);
+  assert_eq!(plaintext, b"Live long and prosper.");
+}
+
+// RFC 7518, apéndice C: la clave que acuerdan Alice y Bob con ECDH-ES y
+// A128GCM es `VqqN6vgjbSBcIijNcacQGg`
+#[test]
+fn rfc7518_appendix_c_key_agreement() {
+  let header = json!({
+    "alg": "ECDH-ES",
+    "enc": "A128GCM",
+    "apu": "QWxpY2U",
+    "apv": "Qm9i",
+    "epk": {
+      "kty": "EC",
+      "crv": "P-256",
+      "x": "gI0GAILBdu7T53akrFmMyGcsF3n5dO7MmwNBHKW5SV0",
+      "y": "SLW_xSffzlPWrHEVI30DHM_4egVwt3NQqeUD7nMFpps",
+    },
+  });
+  let token = seal(header, &decode("VqqN6vgjbSBcIijNcacQGg"), PLAINTEXT);
+  let bob = Jwk::from_json(
+    r#"{"kty":"EC","crv":"P-256",
+      "x":"weNJy2HscCSM6AEDTDg04biOvhFhyyWvOHQfeF_PxMQ",
+      "y":"e8lnCO-AlStT-NJVX-crhB7QRYhiix03illJOVAOyck",
+      "d":"VEmDZpDXXK8p8N0Cndsxs924q6nS1RXFASRl6BfUqdw"}"#,
+  )
+  .unwrap();
+  let key = DecryptionKey::from_material(
+    JweAlgorithm::EcdhEs,
+    &KeyMaterial::Jwk(Box::new(bob)),
+  )
+  .unwrap();
+  assert_eq!(key.decrypt(&token).unwrap().1, PLAINTEXT);
+}
+
+#[test]
+fn unknown_critical_parameters_are_rejected() {
+  let enc = ContentEncryption::A128GCM;
+  let (encryption_key, decryption_key) = key_pair(JweAlgorithm::Dir, enc, 1);
+  let mut header = JweHeader::new(JweAlgorithm::Dir, enc);
+  header.crit = Some(vec!["exp-ext".to_string()]);
+  header.extra.insert("exp-ext".to_string(), json!(1));
+  let token = encryption_key.encrypt(header, PLAINTEXT).unwrap();
+  assert_eq!(
+    decryption_key.decrypt(&token).unwrap_err(),
+    JwtError::UnsupportedCritical { parameter: "exp-ext".to_string() }
+  );
+
+  // Un `crit` mal formado no se puede crear ni se acepta
+  let key = [1u8; 16];
+  for (crit, extra) in [
+    (json!([]), json!({})),
+    (json!(["enc"]), json!({})),
+    (json!(["exp-ext"]), json!({})),
+    (json!(["exp-ext", "exp-ext"]), json!({ "exp-ext": 1 })),
+  ] {
+    let mut header = JweHeader::new(JweAlgorithm::Dir, enc);
+    header.crit = serde_json::from_value(crit.clone()).unwrap();
+    header.extra = serde_json::from_value(extra.clone()).unwrap();
+    let result = encryption_key.encrypt(header, PLAINTEXT);
+    assert!(matches!(result, Err(JwtError::InvalidOptions(_))), "{crit}");
+
+    let mut header = json!({ "alg": "dir", "enc": "A128GCM", "crit": crit });
+    header.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    let result = decryption_key.decrypt(&seal(header, &key, PLAINTEXT));
+    assert!(matches!(result, Err(JwtError::Malformed(_))), "{crit}");
+  }
+}
+
+#[test]
+fn oversized_headers_are_rejected_up_front() {
+  let key = [1u8; 16];
+  let (_, decryption_key) =
+    key_pair(JweAlgorithm::Dir, ContentEncryption::A128GCM, 1);
+  let header = |padding: usize| json!({ "alg": "dir", "enc": "A128GCM", "pad": "x".repeat(padding) });
+
+  let token = seal(header(1024), &key, PLAINTEXT);
+  assert!(JweHeader::decode(&token).is_ok());
+  assert_eq!(decryption_key.decrypt(&token).unwrap().1, PLAINTEXT);
+
+  // El mismo límite que la cabecera de un JWS: 8192 caracteres codificados
+  let token = seal(header(8192), &key, PLAINTEXT);
+  let too_large = JwtError::Malformed("Token header is too large".to_string());
+  assert_eq!(JweHeader::decode(&token).unwrap_err(), too_large);
+  assert_eq!(decryption_key.decrypt(&token).unwrap_err(), too_large);
+}
+
+#[test]
+fn decrypt_options_restrict_alg_and_enc() {
+  let alg = JweAlgorithm::A256KW;
+  let enc = ContentEncryption::A128CbcHs256;
+  let (encryption_key, decryption_key) = key_pair(alg, enc, 1);
+  let token =
+    encryption_key.encrypt(JweHeader::new(alg, enc), PLAINTEXT).unwrap();
+  let options = |options: Value| -> DecryptOptions {
+    serde_json::from_value(options).unwrap()
+  };
+
+  let allowed = options(json!({
+    "allowed_algorithms": ["A256KW", "ECDH-ES"],
+    "allowed_encryptions": ["A128CBC-HS256"],
+  }));
+  assert!(decryption_key.decrypt_with(&token, &allowed).is_ok());
+  for rejected in [
+    json!({ "allowed_algorithms": ["dir", "A128KW"] }),
+    json!({ "allowed_encryptions": ["A256GCM"] }),
+    json!({ "allowed_algorithms": [] }),
+  ] {
+    let result = decryption_key.decrypt_with(&token, &options(rejected));
+    assert_eq!(result.unwrap_err(), JwtError::AlgorithmMismatch);
+  }
+
+  // La cabecera se comprueba antes de tocar la clave
+  let header = JweHeader::decode(&token).unwrap();
+  let result =
+    options(json!({ "allowed_algorithms": ["dir"] })).check_header(&header);
+  assert_eq!(result.unwrap_err(), JwtError::AlgorithmMismatch);
+  assert!(DecryptOptions::default().check_header(&header).is_ok());
+}
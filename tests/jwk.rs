@@ -0,0 +1,215 @@
+use jwt_simple::prelude::*;
+use jwt_wasm::core::public_jwk;
+use jwt_wasm::{
+  Algorithm, Jwk, JwkSet, JwtError, KeyMaterial, SigningKey, VerifyOptions,
+  VerifyingKey,
+};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+fn claims() -> JWTClaims<Value> {
+  Claims::with_custom_claims(json!({ "scope": "read" }), Duration::from_mins(5))
+}
+
+// Generar claves RSA es lento: todas las pruebas comparten la misma
+fn keys() -> Vec<SigningKey> {
+  static RSA: OnceLock<String> = OnceLock::new();
+  let pem = RSA.get_or_init(|| {
+    SigningKey::generate(Algorithm::RS256, None).unwrap().to_pem().unwrap()
+  });
+  let rsa = KeyMaterial::Pem(pem.as_str().into());
+  let mut keys = vec![
+    SigningKey::from_secret(Algorithm::HS256, SECRET).unwrap(),
+    SigningKey::from_material(Algorithm::RS256, &rsa).unwrap(),
+  ];
+  for algorithm in
+    [Algorithm::ES256, Algorithm::ES384, Algorithm::ES256K, Algorithm::EdDSA]
+  {
+    keys.push(SigningKey::generate(algorithm, None).unwrap());
+  }
+  keys
+}
+
+fn material(jwk: &Jwk) -> KeyMaterial {
+  KeyMaterial::Jwk(Box::new(jwk.clone()))
+}
+
+#[test]
+fn keys_survive_an_export_and_import() {
+  for key in keys() {
+    let algorithm = key.algorithm();
+    let jwk = key.to_jwk().unwrap();
+    assert_eq!(jwk.alg.as_deref(), Some(algorithm.as_str()));
+    assert_eq!(jwk.key_use.as_deref(), Some("sig"));
+    assert!(jwk.is_private(), "{algorithm}");
+    assert_eq!(Jwk::from_json(&jwk.to_json().unwrap()).unwrap(), jwk);
+
+    // La clave importada firma tokens que acepta la original
+    let imported =
+      SigningKey::from_material(algorithm, &material(&jwk)).unwrap();
+    let token = imported.sign(claims()).unwrap();
+    let options = VerifyOptions::default();
+    assert!(key.verifying_key().verify_with(&token, &options).is_ok());
+
+    // y la parte pública verifica los tokens de la original
+    let public = key.verifying_key().to_jwk().unwrap();
+    let verifier =
+      VerifyingKey::from_material(algorithm, &material(&public)).unwrap();
+    let token = key.sign(claims()).unwrap();
+    assert!(verifier.verify_with(&token, &options).is_ok(), "{algorithm}");
+  }
+}
+
+#[test]
+fn key_types_and_curves_follow_the_algorithm() {
+  let kinds: Vec<_> = keys()
+    .iter()
+    .map(|key| {
+      let jwk = key.verifying_key().to_jwk().unwrap();
+      (jwk.kty, jwk.crv)
+    })
+    .collect();
+  let curve = |crv: &str| Some(crv.to_string());
+  assert_eq!(
+    kinds,
+    [
+      ("oct".to_string(), None),
+      ("RSA".to_string(), None),
+      ("EC".to_string(), curve("P-256")),
+      ("EC".to_string(), curve("P-384")),
+      ("EC".to_string(), curve("secp256k1")),
+      ("OKP".to_string(), curve("Ed25519")),
+    ]
+  );
+}
+
+#[test]
+fn public_keys_drop_the_private_members() {
+  for key in keys().into_iter().skip(1) {
+    let public = key.to_jwk().unwrap().to_public().unwrap();
+    assert!(!public.is_private());
+    assert!(public.p.is_none() && public.q.is_none() && public.qi.is_none());
+    assert_eq!(public, key.verifying_key().to_jwk().unwrap());
+    assert!(!public.to_json().unwrap().contains("\"d\""));
+  }
+
+  // Un secreto HMAC no tiene parte pública
+  let jwk = keys()[0].to_jwk().unwrap();
+  assert!(matches!(jwk.to_public(), Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn supports_checks_type_curve_alg_and_use() {
+  let jwk = SigningKey::generate(Algorithm::ES256, None)
+    .unwrap()
+    .verifying_key()
+    .to_jwk()
+    .unwrap();
+  assert!(jwk.supports(Algorithm::ES256));
+  assert!(!jwk.supports(Algorithm::ES384));
+  assert!(!jwk.supports(Algorithm::RS256));
+
+  let unbound = Jwk { alg: None, ..jwk.clone() };
+  assert!(unbound.supports(Algorithm::ES256));
+  let encryption = Jwk { key_use: Some("enc".to_string()), ..jwk.clone() };
+  assert!(!encryption.supports(Algorithm::ES256));
+
+  // Una clave de otro tipo no se importa
+  let result = VerifyingKey::from_material(Algorithm::ES384, &material(&jwk));
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn public_jwk_accepts_private_material() {
+  let key = SigningKey::generate(Algorithm::EdDSA, None).unwrap();
+  let pem = KeyMaterial::Pem(key.to_pem().unwrap().into());
+  let jwk =
+    public_jwk(Algorithm::EdDSA, &pem, Some("ed-1".to_string())).unwrap();
+  assert_eq!(jwk.kid.as_deref(), Some("ed-1"));
+  assert!(!jwk.is_private());
+  assert_eq!(Jwk { kid: None, ..jwk }, key.verifying_key().to_jwk().unwrap());
+}
+
+// Conjunto con dos claves EC y una Ed25519, cada una con su `kid`
+fn key_set() -> (Vec<SigningKey>, JwkSet) {
+  let mut keys = Vec::new();
+  let mut jwks = Vec::new();
+  for (kid, algorithm) in [
+    ("ec-1", Algorithm::ES256),
+    ("ec-2", Algorithm::ES256),
+    ("ed-1", Algorithm::EdDSA),
+  ] {
+    let key = SigningKey::generate(algorithm, None).unwrap();
+    jwks.push(Jwk { kid: Some(kid.to_string()), ..key.to_jwk().unwrap() });
+    keys.push(key.with_key_id(kid));
+  }
+  (keys, JwkSet::new(jwks))
+}
+
+#[test]
+fn jwks_round_trip_as_public_json() {
+  let (_, jwks) = key_set();
+  let public = jwks.to_public();
+  assert!(public.keys().iter().all(|jwk| !jwk.is_private()));
+
+  let json = serde_json::to_string(&public).unwrap();
+  let parsed = JwkSet::from_json(&json).unwrap();
+  assert_eq!(parsed.keys(), public.keys());
+  assert!(matches!(JwkSet::from_json("[]"), Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn public_jwks_omit_symmetric_keys() {
+  let (_, jwks) = key_set();
+  let hmac = SigningKey::from_secret(Algorithm::HS256, SECRET).unwrap();
+  let hmac = Jwk { kid: Some("hs-1".to_string()), ..hmac.to_jwk().unwrap() };
+  let mut keys = jwks.keys().to_vec();
+  keys.insert(1, hmac);
+  let mixed = JwkSet::new(keys);
+
+  let public = mixed.to_public();
+  assert_eq!(public.keys().len(), mixed.keys().len() - 1);
+  assert!(public.keys().iter().all(|jwk| jwk.kty != "oct"));
+  assert!(public.keys().iter().all(|jwk| !jwk.is_private()));
+  assert!(public.find(Some("hs-1"), Algorithm::HS256).is_err());
+}
+
+#[test]
+fn jwks_find_selects_by_kid_and_algorithm() {
+  let (_, jwks) = key_set();
+  let kid = |jwk: &Jwk| jwk.kid.clone().unwrap();
+  assert_eq!(kid(jwks.find(Some("ec-2"), Algorithm::ES256).unwrap()), "ec-2");
+  // Sin `kid` se usa la primera clave compatible
+  assert_eq!(kid(jwks.find(None, Algorithm::ES256).unwrap()), "ec-1");
+  assert_eq!(kid(jwks.find(None, Algorithm::EdDSA).unwrap()), "ed-1");
+
+  let result = jwks.find(Some("ec-3"), Algorithm::ES256);
+  assert_eq!(result.unwrap_err(), JwtError::KeyIdMismatch);
+  let result = jwks.find(Some("ed-1"), Algorithm::ES256);
+  assert_eq!(result.unwrap_err(), JwtError::AlgorithmMismatch);
+  let empty = JwkSet::default();
+  let result = empty.find(None, Algorithm::ES256);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn jwks_verify_tokens_with_the_key_in_their_header() {
+  let (keys, jwks) = key_set();
+  let jwks = jwks.to_public();
+  let options = VerifyOptions::default();
+  for key in &keys {
+    let token = key.sign(claims()).unwrap();
+    let verifying_key = jwks.verifying_key(&token).unwrap();
+    assert!(verifying_key.verify_with(&token, &options).is_ok());
+  }
+
+  // Un token firmado con otra clave pero marcado como `ec-2` no se acepta
+  let forged =
+    SigningKey::generate(Algorithm::ES256, None).unwrap().with_key_id("ec-2");
+  let token = forged.sign(claims()).unwrap();
+  let verifying_key = jwks.verifying_key(&token).unwrap();
+  let result = verifying_key.verify_with(&token, &options);
+  assert_eq!(result.unwrap_err(), JwtError::InvalidSignature);
+}
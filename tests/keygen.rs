@@ -0,0 +1,132 @@
+use jwt_simple::prelude::*;
+use jwt_wasm::{
+  generate_secret, Algorithm, ExportedKey, GeneratedKey, JwtError,
+  KeyGenOptions, KeyMaterial, SigningKey, VerifyOptions, VerifyingKey,
+};
+use serde_json::{json, Value};
+
+fn claims() -> JWTClaims<Value> {
+  Claims::with_custom_claims(json!({}), Duration::from_mins(5))
+}
+
+fn options(kid: Option<&str>) -> KeyGenOptions {
+  KeyGenOptions { kid: kid.map(str::to_string), ..Default::default() }
+}
+
+fn pem(key: &ExportedKey) -> KeyMaterial {
+  KeyMaterial::Pem(key.pem.clone().unwrap())
+}
+
+#[test]
+fn hmac_keys_are_random_secrets() {
+  for (algorithm, encoded_length) in
+    [(Algorithm::HS256, 43), (Algorithm::HS384, 64), (Algorithm::HS512, 86)]
+  {
+    let generated = GeneratedKey::generate(algorithm, &options(None)).unwrap();
+    let secret = generated.secret.as_ref().unwrap().expose();
+    // Tantos bytes aleatorios como el hash, en base64url
+    assert_eq!(secret.len(), encoded_length);
+    assert!(generated.public_key.is_none());
+    assert!(generated.private_key.pem.is_none());
+    assert_eq!(generated.private_key.jwk.kty, "oct");
+    assert_eq!(
+      generated.private_key.jwk.alg.as_deref(),
+      Some(algorithm.as_str())
+    );
+
+    let other = GeneratedKey::generate(algorithm, &options(None)).unwrap();
+    assert_ne!(other.secret, generated.secret);
+
+    let key = SigningKey::from_secret(algorithm, secret.as_bytes()).unwrap();
+    let token = key.sign(claims()).unwrap();
+    let jwk = KeyMaterial::Jwk(Box::new(generated.private_key.jwk));
+    let verifying_key = VerifyingKey::from_material(algorithm, &jwk).unwrap();
+    assert!(verifying_key
+      .verify_with(&token, &VerifyOptions::default())
+      .is_ok());
+  }
+}
+
+#[test]
+fn key_pairs_export_both_halves() {
+  let options = options(Some("key-1"));
+  for algorithm in [
+    Algorithm::PS256,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::ES256K,
+    Algorithm::EdDSA,
+  ] {
+    let generated = GeneratedKey::generate(algorithm, &options).unwrap();
+    assert!(generated.secret.is_none());
+    assert_eq!(generated.kid.as_deref(), Some("key-1"));
+    let public = generated.public_key.as_ref().unwrap();
+    assert_eq!(generated.private_key.jwk.kid.as_deref(), Some("key-1"));
+    assert_eq!(public.jwk.kid.as_deref(), Some("key-1"));
+    assert!(generated.private_key.jwk.is_private());
+    assert!(!public.jwk.is_private());
+
+    // La clave privada firma y las dos exportaciones públicas verifican
+    let key =
+      SigningKey::from_material(algorithm, &pem(&generated.private_key))
+        .unwrap();
+    let token = key.sign(claims()).unwrap();
+    let jwk = KeyMaterial::Jwk(Box::new(public.jwk.clone()));
+    for material in [pem(public), jwk] {
+      let verifying_key =
+        VerifyingKey::from_material(algorithm, &material).unwrap();
+      let result = verifying_key.verify_with(&token, &VerifyOptions::default());
+      assert!(result.is_ok(), "{algorithm}");
+    }
+  }
+}
+
+#[test]
+fn generated_keys_serialize_their_secrets() {
+  let generated =
+    GeneratedKey::generate(Algorithm::HS256, &options(Some("k"))).unwrap();
+  let json = serde_json::to_value(&generated).unwrap();
+  assert_eq!(json["algorithm"], "HS256");
+  assert_eq!(json["kid"], "k");
+  assert_eq!(json["secret"], *generated.secret.as_ref().unwrap().expose());
+  assert!(json.get("public_key").is_none());
+
+  let generated =
+    GeneratedKey::generate(Algorithm::EdDSA, &options(None)).unwrap();
+  let json = serde_json::to_value(&generated).unwrap();
+  assert!(json.get("secret").is_none());
+  assert!(json.get("kid").is_none());
+  let private_pem = json["private_key"]["pem"].as_str().unwrap();
+  assert!(private_pem.contains("PRIVATE KEY"));
+  assert!(json["public_key"]["pem"].as_str().unwrap().contains("PUBLIC KEY"));
+  assert!(json["private_key"]["jwk"]["d"].is_string());
+}
+
+#[test]
+fn secret_length_is_configurable() {
+  let options = KeyGenOptions { secret_length: Some(64), ..Default::default() };
+  let generated = GeneratedKey::generate(Algorithm::HS256, &options).unwrap();
+  assert_eq!(generated.secret.unwrap().expose().len(), 86);
+
+  // Un secreto más corto que el hash no se acepta
+  let options = KeyGenOptions { secret_length: Some(8), ..Default::default() };
+  let result = GeneratedKey::generate(Algorithm::HS256, &options);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn rsa_modulus_sizes_are_checked() {
+  let options =
+    KeyGenOptions { modulus_bits: Some(1024), ..Default::default() };
+  let result = GeneratedKey::generate(Algorithm::RS256, &options);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn secrets_are_random_base64url() {
+  let secret = generate_secret(16).unwrap();
+  assert_eq!(secret.len(), 22);
+  assert!(Base64UrlSafeNoPadding::decode_to_vec(&secret, None).is_ok());
+  assert_ne!(generate_secret(16).unwrap(), secret);
+  assert!(matches!(generate_secret(0), Err(JwtError::InvalidOptions(_))));
+}
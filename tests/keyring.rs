@@ -0,0 +1,172 @@
+use jwt_simple::prelude::*;
+use jwt_wasm::{
+  Algorithm, Jwk, JwtError, KeyMaterial, KeyRing, SigningKey, VerifyOptions,
+};
+use serde_json::{json, Value};
+
+const HOUR: u64 = 60 * 60;
+
+fn claims() -> JWTClaims<Value> {
+  Claims::with_custom_claims(json!({}), Duration::from_mins(5))
+}
+
+fn now() -> u64 {
+  Clock::now_since_epoch().as_secs()
+}
+
+fn hmac(secret: &str) -> SigningKey {
+  SigningKey::from_secret(Algorithm::HS256, secret.repeat(32).as_bytes())
+    .unwrap()
+}
+
+fn kid(token: &str) -> String {
+  Token::decode_metadata(token).unwrap().key_id().unwrap().to_string()
+}
+
+#[test]
+fn the_newest_valid_key_signs() {
+  let mut ring = KeyRing::default();
+  let now = now();
+  ring.add_signing_key("old", hmac("a"), Some(now - 2 * HOUR), None).unwrap();
+  ring.add_signing_key("new", hmac("b"), Some(now - HOUR), None).unwrap();
+  // Una clave que todavía no es válida no firma
+  ring.add_signing_key("next", hmac("c"), Some(now + HOUR), None).unwrap();
+  assert_eq!(ring.active_key().unwrap().kid(), "new");
+
+  let token = ring.sign(claims()).unwrap();
+  assert_eq!(kid(&token), "new");
+  assert!(ring.verify_with(&token, &VerifyOptions::default()).is_ok());
+
+  // Al retirar la clave activa firma la anterior
+  assert!(ring.remove("new"));
+  assert!(!ring.remove("new"));
+  assert_eq!(kid(&ring.sign(claims()).unwrap()), "old");
+}
+
+#[test]
+fn tokens_are_verified_with_the_key_of_their_kid() {
+  let mut ring = KeyRing::default();
+  ring.add_signing_key("a", hmac("a"), None, None).unwrap();
+  let token = ring.sign(claims()).unwrap();
+
+  // El token de otra clave con el mismo `kid` no pasa la firma
+  let forged = hmac("b").with_key_id("a").sign(claims()).unwrap();
+  let options = VerifyOptions::default();
+  let result = ring.verify_with(&forged, &options);
+  assert_eq!(result.unwrap_err(), JwtError::InvalidSignature);
+
+  let unknown = hmac("a").with_key_id("z").sign(claims()).unwrap();
+  let result = ring.verify_with(&unknown, &options);
+  assert_eq!(result.unwrap_err(), JwtError::KeyIdMismatch);
+  let anonymous = hmac("a").sign(claims()).unwrap();
+  let result = ring.verify_with(&anonymous, &options);
+  assert_eq!(result.unwrap_err(), JwtError::KeyIdMismatch);
+
+  let verified = ring.verify_full(&token, &options).unwrap();
+  assert_eq!(verified.header.kid.as_deref(), Some("a"));
+}
+
+#[test]
+fn retired_keys_verify_during_the_grace_period() {
+  let retired_at = now() - HOUR;
+  let token = hmac("a").with_key_id("a").sign(claims()).unwrap();
+  let ring = |grace_period: u64| {
+    let mut ring = KeyRing::with_grace_period(grace_period);
+    ring.add_signing_key("a", hmac("a"), None, Some(retired_at)).unwrap();
+    ring
+  };
+  let options = VerifyOptions::default();
+
+  let result = ring(0).verify_with(&token, &options);
+  assert_eq!(
+    result.unwrap_err(),
+    JwtError::KeyRetired { retired_at: Some(retired_at) }
+  );
+  let result = ring(30 * 60 * 1000).verify_with(&token, &options);
+  assert!(matches!(result, Err(JwtError::KeyRetired { .. })));
+  assert!(ring(2 * HOUR * 1000).verify_with(&token, &options).is_ok());
+
+  // Una clave retirada ya no firma
+  let result = ring(2 * HOUR * 1000).sign(claims());
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn verifying_keys_never_sign() {
+  let key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let mut ring = KeyRing::default();
+  ring.add_verifying_key("ec", key.verifying_key(), None, None).unwrap();
+  assert!(matches!(ring.active_key(), Err(JwtError::InvalidKey(_))));
+
+  let token = key.with_key_id("ec").sign(claims()).unwrap();
+  assert!(ring.verify_with(&token, &VerifyOptions::default()).is_ok());
+  let entry = &ring.keys()[0];
+  assert_eq!((entry.kid(), entry.algorithm()), ("ec", Algorithm::ES256));
+}
+
+#[test]
+fn key_ids_are_unique() {
+  let mut ring = KeyRing::default();
+  ring.add_signing_key("a", hmac("a"), None, None).unwrap();
+  let result = ring.add_signing_key("a", hmac("b"), None, None);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+  assert_eq!(ring.keys().len(), 1);
+}
+
+#[test]
+fn material_is_added_as_a_signing_or_verifying_key() {
+  let key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let public = key.verifying_key().to_jwk().unwrap();
+  let decode = |value: &Option<String>| {
+    Base64UrlSafeNoPadding::decode_to_vec(value.as_ref().unwrap(), None)
+      .unwrap()
+  };
+  let point = [vec![0x04], decode(&public.x), decode(&public.y)].concat();
+  let materials = [
+    ("private-pem", KeyMaterial::Pem(key.to_pem().unwrap().into()), true),
+    ("private-jwk", KeyMaterial::Jwk(Box::new(key.to_jwk().unwrap())), true),
+    (
+      "public-pem",
+      KeyMaterial::Pem(key.verifying_key().to_pem().unwrap().into()),
+      false,
+    ),
+    ("public-jwk", KeyMaterial::Jwk(Box::new(public.clone())), false),
+    ("public-der", KeyMaterial::Der(point.into()), false),
+  ];
+
+  let mut ring = KeyRing::default();
+  for (kid, material, can_sign) in &materials {
+    ring.add_material(kid, Algorithm::ES256, material, None, None).unwrap();
+    let mut only = KeyRing::default();
+    only.add_material(kid, Algorithm::ES256, material, None, None).unwrap();
+    assert_eq!(only.active_key().is_ok(), *can_sign, "{kid}");
+  }
+  assert_eq!(ring.keys().len(), materials.len());
+}
+
+#[test]
+fn broken_private_material_is_not_added_for_verification() {
+  let key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let other = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let mut ring = KeyRing::default();
+
+  // Un JWK cuya parte privada no corresponde a la pública
+  let jwk = Jwk { d: other.to_jwk().unwrap().d, ..key.to_jwk().unwrap() };
+  let material = KeyMaterial::Jwk(Box::new(jwk));
+  let result =
+    ring.add_material("jwk", Algorithm::ES256, &material, None, None);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+
+  // Un PEM privado dañado
+  let pem = key.to_pem().unwrap().replacen("MI", "XX", 1);
+  let material = KeyMaterial::Pem(pem.into());
+  let result =
+    ring.add_material("pem", Algorithm::ES256, &material, None, None);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+
+  let material = KeyMaterial::Der(vec![1, 2, 3].into());
+  let result =
+    ring.add_material("der", Algorithm::ES256, &material, None, None);
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+  assert!(ring.keys().is_empty());
+}
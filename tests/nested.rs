@@ -0,0 +1,217 @@
+use jwt_simple::prelude::*;
+use jwt_wasm::{
+  decrypt_and_verify, sign_and_encrypt, Algorithm, ContentEncryption,
+  DecryptOptions, DecryptionKey, EncryptionKey, JweAlgorithm, JweHeader,
+  JwtError, JwtOptions, KeyMaterial, SigningKey, TokenHeader, VerifyOptions,
+};
+use serde_json::{json, Value};
+
+const KEY: [u8; 32] = [9; 32];
+
+fn claims() -> JWTClaims<Value> {
+  Claims::with_custom_claims(json!({ "role": "admin" }), Duration::from_mins(5))
+    .with_issuer("https://auth.example.com")
+}
+
+fn jws_header() -> TokenHeader {
+  JwtOptions::default().token_header().unwrap()
+}
+
+fn header() -> JweHeader {
+  JweHeader::new(JweAlgorithm::A256KW, ContentEncryption::A256GCM)
+}
+
+fn encryption_key() -> EncryptionKey {
+  EncryptionKey::from_secret(JweAlgorithm::A256KW, &KEY).unwrap()
+}
+
+fn decryption_key() -> DecryptionKey {
+  DecryptionKey::from_secret(JweAlgorithm::A256KW, &KEY).unwrap()
+}
+
+#[test]
+fn signed_tokens_are_encrypted_and_verified() {
+  let signing_key =
+    SigningKey::generate(Algorithm::EdDSA, None).unwrap().with_key_id("ed-1");
+  let token = sign_and_encrypt(
+    claims(),
+    &signing_key,
+    jws_header(),
+    &encryption_key(),
+    header(),
+  )
+  .unwrap();
+
+  // La cabecera exterior anuncia un JWT anidado
+  let outer = JweHeader::decode(&token).unwrap();
+  assert_eq!(outer.cty.as_deref(), Some("JWT"));
+  assert_eq!(outer.alg, JweAlgorithm::A256KW);
+
+  let verified = decrypt_and_verify(
+    &token,
+    &decryption_key(),
+    &DecryptOptions::default(),
+    &signing_key.verifying_key(),
+    &VerifyOptions::default(),
+  )
+  .unwrap();
+  // La cabecera devuelta es la del JWS interior
+  assert_eq!(verified.header.alg, "EdDSA");
+  assert_eq!(verified.header.kid.as_deref(), Some("ed-1"));
+  assert_eq!(verified.claims.iss.as_deref(), Some("https://auth.example.com"));
+  assert_eq!(verified.payload, json!({ "role": "admin" }));
+}
+
+#[test]
+fn the_inner_token_is_a_regular_jws() {
+  let signing_key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let token = sign_and_encrypt(
+    claims(),
+    &signing_key,
+    jws_header(),
+    &encryption_key(),
+    header(),
+  )
+  .unwrap();
+  let jws = decryption_key()
+    .decrypt_nested(&token, &DecryptOptions::default())
+    .unwrap();
+  assert_eq!(jws.split('.').count(), 3);
+  let options = VerifyOptions::default();
+  assert!(signing_key.verifying_key().verify_with(&jws, &options).is_ok());
+
+  // Un JWE sin `cty: "JWT"` no es un token anidado
+  let plain = encryption_key().encrypt(header(), jws.as_bytes()).unwrap();
+  let result = decryption_key().decrypt_nested(&plain, &Default::default());
+  assert!(matches!(result, Err(JwtError::Malformed(_))));
+}
+
+#[test]
+fn the_inner_header_comes_from_the_options() {
+  let options: JwtOptions = serde_json::from_value(json!({
+    "expires_in": "5m",
+    "typ": "at+jwt",
+    "cty": "example",
+    "header": { "tenant": "acme" },
+    "crit": ["tenant"],
+  }))
+  .unwrap();
+  let signing_key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let token = sign_and_encrypt(
+    claims(),
+    &signing_key,
+    options.token_header().unwrap(),
+    &encryption_key(),
+    header(),
+  )
+  .unwrap();
+
+  let jws = decryption_key()
+    .decrypt_nested(&token, &DecryptOptions::default())
+    .unwrap();
+  let inner = TokenHeader::decode(&jws).unwrap();
+  assert_eq!(inner.typ.as_deref(), Some("at+jwt"));
+  assert_eq!(inner.cty.as_deref(), Some("example"));
+  assert_eq!(inner.extra["tenant"], "acme");
+  assert_eq!(inner.crit, Some(vec!["tenant".to_string()]));
+
+  // El `crit` del JWS interior se sigue exigiendo al verificar
+  let result = decrypt_and_verify(
+    &token,
+    &decryption_key(),
+    &DecryptOptions::default(),
+    &signing_key.verifying_key(),
+    &VerifyOptions::default(),
+  );
+  assert_eq!(
+    result.unwrap_err(),
+    JwtError::UnsupportedCritical { parameter: "tenant".to_string() }
+  );
+}
+
+#[test]
+fn each_layer_applies_its_own_rules() {
+  let signing_key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let token = sign_and_encrypt(
+    claims(),
+    &signing_key,
+    jws_header(),
+    &encryption_key(),
+    header(),
+  )
+  .unwrap();
+  let verify = |decrypt_options: Value, verifying_key: &SigningKey, options| {
+    let decrypt_options: DecryptOptions =
+      serde_json::from_value(decrypt_options).unwrap();
+    let options: VerifyOptions = serde_json::from_value(options).unwrap();
+    decrypt_and_verify(
+      &token,
+      &decryption_key(),
+      &decrypt_options,
+      &verifying_key.verifying_key(),
+      &options,
+    )
+  };
+
+  let result =
+    verify(json!({ "allowed_algorithms": ["dir"] }), &signing_key, json!({}));
+  assert_eq!(result.unwrap_err(), JwtError::AlgorithmMismatch);
+
+  let other = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let result = verify(json!({}), &other, json!({}));
+  assert_eq!(result.unwrap_err(), JwtError::InvalidSignature);
+
+  let options = json!({ "allowed_issuers": ["https://other.example.com"] });
+  let result = verify(json!({}), &signing_key, options);
+  assert_eq!(result.unwrap_err(), JwtError::IssuerMismatch);
+
+  let options = json!({ "allowed_issuers": ["https://auth.example.com"] });
+  assert!(verify(
+    json!({ "allowed_algorithms": ["A256KW"] }),
+    &signing_key,
+    options
+  )
+  .is_ok());
+}
+
+#[test]
+fn keys_can_come_from_pem_material() {
+  let signing_key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let recipient = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let public = recipient.verifying_key().to_pem().unwrap();
+  let private = recipient.to_pem().unwrap();
+  let alg = JweAlgorithm::EcdhEsA256KW;
+  let encryption_key =
+    EncryptionKey::from_material(alg, &KeyMaterial::Pem(public.into()))
+      .unwrap();
+  let decryption_key =
+    DecryptionKey::from_material(alg, &KeyMaterial::Pem(private.into()))
+      .unwrap();
+
+  let header = JweHeader::new(alg, ContentEncryption::A128CbcHs256);
+  let token = sign_and_encrypt(
+    claims(),
+    &signing_key,
+    jws_header(),
+    &encryption_key,
+    header,
+  )
+  .unwrap();
+  let verified = decrypt_and_verify(
+    &token,
+    &decryption_key,
+    &DecryptOptions::default(),
+    &signing_key.verifying_key(),
+    &VerifyOptions::default(),
+  )
+  .unwrap();
+  assert_eq!(verified.payload["role"], "admin");
+
+  // Manipular el JWE exterior rompe el descifrado, no la firma
+  let mut parts: Vec<String> = token.split('.').map(str::to_string).collect();
+  let first = if parts[3].starts_with('A') { "B" } else { "A" };
+  parts[3].replace_range(..1, first);
+  let result =
+    decryption_key.decrypt_nested(&parts.join("."), &Default::default());
+  assert_eq!(result.unwrap_err(), JwtError::DecryptionFailed);
+}
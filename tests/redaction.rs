@@ -0,0 +1,59 @@
+use jwt_wasm::{
+  Algorithm, GeneratedKey, JwtOptions, KeyGenOptions, KeyMaterial, Secret,
+  SigningKey,
+};
+use serde_json::json;
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+#[test]
+fn secrets_are_redacted_when_printed() {
+  let secret = Secret::from(SECRET);
+  assert_eq!(format!("{secret:?}"), "[REDACTED]");
+  assert_eq!(secret.to_string(), "[REDACTED]");
+  assert_eq!(secret.expose(), SECRET);
+
+  let bytes = Secret::from(SECRET.as_bytes().to_vec());
+  assert_eq!(format!("{bytes:?}"), "[REDACTED]");
+}
+
+#[test]
+fn options_never_show_their_secret() {
+  let options: JwtOptions = serde_json::from_value(json!({
+    "secret": SECRET,
+    "expires_in": "5m",
+    "issuer": "https://auth.example.com",
+  }))
+  .unwrap();
+  let debug = format!("{options:?}");
+  assert!(!debug.contains(SECRET));
+  assert!(debug.contains("[REDACTED]"));
+  assert!(debug.contains("https://auth.example.com"));
+
+  let json = serde_json::to_value(&options).unwrap();
+  assert!(json.get("secret").is_none());
+  assert_eq!(json["issuer"], "https://auth.example.com");
+
+  let options = JwtOptions::new(SECRET.to_string(), 60_000);
+  assert!(!format!("{options:?}").contains(SECRET));
+}
+
+#[test]
+fn private_key_material_is_redacted() {
+  let key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let jwk = key.to_jwk().unwrap();
+  let d = jwk.d.as_ref().unwrap().expose().clone();
+  assert!(!format!("{jwk:?}").contains(&d));
+  // Al exportar el JWK a propósito sí se incluye
+  assert!(jwk.to_json().unwrap().contains(&d));
+
+  let pem = key.to_pem().unwrap();
+  let material = KeyMaterial::Pem(pem.clone().into());
+  assert!(!format!("{material:?}").contains(&pem));
+
+  let generated =
+    GeneratedKey::generate(Algorithm::HS256, &KeyGenOptions::default())
+      .unwrap();
+  let secret = generated.secret.as_ref().unwrap().expose().clone();
+  assert!(!format!("{generated:?}").contains(&secret));
+}
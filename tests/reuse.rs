@@ -0,0 +1,59 @@
+use jwt_wasm::core::{self, private_signing_key, secret_verifying_key};
+use jwt_wasm::{
+  Algorithm, JwtError, JwtOptions, KeyMaterial, SigningKey, VerifyOptions,
+  VerifyingKey,
+};
+use serde_json::{json, Value};
+
+// `JwtSigner` y `JwtVerifier` guardan la clave y las opciones ya
+// procesadas; aquí se prueba ese mismo uso desde Rust
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+#[test]
+fn one_key_signs_and_verifies_many_tokens() {
+  let options: JwtOptions = serde_json::from_value(json!({
+    "secret": SECRET,
+    "expires_in": "5m",
+    "issuer": "https://auth.example.com",
+  }))
+  .unwrap();
+  let signing_key = core::secret_signing_key(&options).unwrap();
+  let verifying_key =
+    secret_verifying_key(Algorithm::HS256, SECRET.into(), &Default::default())
+      .unwrap();
+  let verify_options: VerifyOptions = serde_json::from_value(json!({
+    "allowed_issuers": ["https://auth.example.com"],
+  }))
+  .unwrap();
+
+  for n in 0..100 {
+    let token = core::sign(&json!({ "n": n }), &signing_key, &options).unwrap();
+    let payload: Value =
+      core::verify(&token, &verifying_key, &verify_options).unwrap();
+    assert_eq!(payload["n"], n);
+  }
+}
+
+#[test]
+fn verifier_only_accepts_its_algorithm() {
+  let signing_key = SigningKey::generate(Algorithm::ES256, None).unwrap();
+  let pem = KeyMaterial::Pem(signing_key.to_pem().unwrap().into());
+  let mut options = JwtOptions::new(String::new(), 60_000);
+  options.set_algorithm("ES256").unwrap();
+  let signing_key = private_signing_key(&options, &pem).unwrap();
+  let token = core::sign(&json!({}), &signing_key, &options).unwrap();
+
+  let public =
+    KeyMaterial::Pem(signing_key.verifying_key().to_pem().unwrap().into());
+  let verifier =
+    VerifyingKey::from_material(Algorithm::ES256, &public).unwrap();
+  assert_eq!(verifier.algorithm(), Algorithm::ES256);
+  assert!(verifier.verify_with(&token, &VerifyOptions::default()).is_ok());
+
+  // Un token HMAC no pasa por el verificador de ES256
+  let hmac_options = JwtOptions::new(SECRET.to_string(), 60_000);
+  let hmac_key = core::secret_signing_key(&hmac_options).unwrap();
+  let token = core::sign(&json!({}), &hmac_key, &hmac_options).unwrap();
+  let result = verifier.verify_with(&token, &VerifyOptions::default());
+  assert_eq!(result.unwrap_err(), JwtError::AlgorithmMismatch);
+}
@@ -0,0 +1,92 @@
+use jwt_wasm::core::{self, secret_signing_key, secret_verifying_key};
+use jwt_wasm::{
+  generate_secret, Algorithm, JwtError, JwtOptions, SigningKey, VerifyOptions,
+  VerifyingKey,
+};
+use serde_json::{json, Value};
+
+fn options(options: Value) -> JwtOptions {
+  serde_json::from_value(options).unwrap()
+}
+
+#[test]
+fn there_is_no_default_secret() {
+  let result = secret_signing_key(&JwtOptions::default());
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+  let result = secret_signing_key(&options(json!({ "expires_in": "5m" })));
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+}
+
+#[test]
+fn secrets_must_be_as_long_as_the_hash() {
+  for (algorithm, length) in
+    [(Algorithm::HS256, 32), (Algorithm::HS384, 48), (Algorithm::HS512, 64)]
+  {
+    let short = "s".repeat(length - 1);
+    let result = SigningKey::from_secret(algorithm, short.as_bytes());
+    assert_eq!(
+      result.err(),
+      Some(JwtError::InvalidKey(format!(
+        "Secret for {algorithm} must be at least {length} bytes long"
+      )))
+    );
+    let result = VerifyingKey::from_secret(algorithm, short.as_bytes());
+    assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+
+    let secret = "s".repeat(length);
+    assert!(SigningKey::from_secret(algorithm, secret.as_bytes()).is_ok());
+    assert!(VerifyingKey::from_secret(algorithm, secret.as_bytes()).is_ok());
+  }
+}
+
+#[test]
+fn weak_secrets_need_an_explicit_opt_out() {
+  let strict = options(json!({ "secret": "short", "expires_in": "5m" }));
+  assert!(matches!(secret_signing_key(&strict), Err(JwtError::InvalidKey(_))));
+
+  let weak = options(json!({
+    "secret": "short",
+    "expires_in": "5m",
+    "allow_weak_secret": true,
+  }));
+  let key = secret_signing_key(&weak).unwrap();
+  let token = core::sign(&json!({}), &key, &weak).unwrap();
+
+  // Al verificar también hay que aceptarlos expresamente
+  let result = secret_verifying_key(
+    Algorithm::HS256,
+    "short".into(),
+    &VerifyOptions::default(),
+  );
+  assert!(matches!(result, Err(JwtError::InvalidKey(_))));
+  let options = VerifyOptions { allow_weak_secret: true, ..Default::default() };
+  let key =
+    secret_verifying_key(Algorithm::HS256, "short".into(), &options).unwrap();
+  assert!(core::verify::<Value>(&token, &key, &options).is_ok());
+}
+
+#[test]
+fn the_opt_out_keeps_basic_checks() {
+  let pem = SigningKey::generate(Algorithm::ES256, None)
+    .unwrap()
+    .verifying_key()
+    .to_pem()
+    .unwrap();
+  let jwk = r#"{"kty":"oct","k":"c2VjcmV0"}"#;
+  for secret in ["", pem.as_str(), jwk] {
+    let result =
+      SigningKey::from_weak_secret(Algorithm::HS256, secret.as_bytes());
+    assert!(matches!(result, Err(JwtError::InvalidKey(_))), "{secret}");
+    let result =
+      VerifyingKey::from_weak_secret(Algorithm::HS256, secret.as_bytes());
+    assert!(matches!(result, Err(JwtError::InvalidKey(_))), "{secret}");
+  }
+}
+
+#[test]
+fn generated_secrets_are_strong_enough() {
+  for algorithm in [Algorithm::HS256, Algorithm::HS384, Algorithm::HS512] {
+    let secret = generate_secret(algorithm.hash_len()).unwrap();
+    assert!(SigningKey::from_secret(algorithm, secret.as_bytes()).is_ok());
+  }
+}
@@ -0,0 +1,147 @@
+use jwt_simple::prelude::*;
+use jwt_wasm::{Algorithm, JwtError, SigningKey, VerifyOptions};
+use serde_json::{json, Value};
+
+const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+const HOUR: u64 = 60 * 60;
+
+fn key() -> SigningKey {
+  SigningKey::from_secret(Algorithm::HS256, SECRET).unwrap()
+}
+
+fn claims() -> JWTClaims<Value> {
+  Claims::with_custom_claims(json!({}), Duration::from_mins(5))
+    .with_issuer("https://auth.example.com")
+    .with_audience("api")
+    .with_subject("user-42")
+    .with_jwt_id("token-1")
+}
+
+// Mueve las marcas de tiempo del token `offset` segundos al pasado
+fn issued_ago(mut claims: JWTClaims<Value>, offset: u64) -> JWTClaims<Value> {
+  let shift = |time: Option<UnixTimeStamp>| {
+    time.map(|time| time - Duration::from_secs(offset))
+  };
+  claims.issued_at = shift(claims.issued_at);
+  claims.invalid_before = shift(claims.invalid_before);
+  claims.expires_at = shift(claims.expires_at);
+  claims
+}
+
+fn verify(
+  claims: JWTClaims<Value>,
+  options: Value,
+) -> Result<JWTClaims<Value>, JwtError> {
+  let key = key();
+  let token = key.sign(claims)?;
+  let options: VerifyOptions = serde_json::from_value(options).unwrap();
+  key.verifying_key().verify_with(&token, &options)
+}
+
+#[test]
+fn empty_options_check_only_the_times() {
+  assert!(verify(claims(), json!({})).is_ok());
+}
+
+#[test]
+fn allowed_issuers() {
+  let options = json!({ "allowed_issuers": ["https://auth.example.com"] });
+  assert!(verify(claims(), options).is_ok());
+
+  let options = json!({ "allowed_issuers": ["https://other.example.com"] });
+  let result = verify(claims(), options.clone());
+  assert_eq!(result.unwrap_err(), JwtError::IssuerMismatch);
+
+  let mut claims = claims();
+  claims.issuer = None;
+  assert_eq!(verify(claims, options).unwrap_err(), JwtError::IssuerMismatch);
+}
+
+#[test]
+fn allowed_audiences() {
+  let options = json!({ "allowed_audiences": ["api", "admin"] });
+  assert!(verify(claims(), options).is_ok());
+
+  let options = json!({ "allowed_audiences": ["billing"] });
+  let result = verify(claims(), options.clone());
+  assert_eq!(result.unwrap_err(), JwtError::AudienceMismatch);
+
+  let claims = claims().with_audiences(HashSet::from(["admin", "billing"]));
+  assert!(verify(claims, options).is_ok());
+}
+
+#[test]
+fn required_subject() {
+  assert!(verify(claims(), json!({ "required_subject": "user-42" })).is_ok());
+  let result = verify(claims(), json!({ "required_subject": "user-7" }));
+  assert_eq!(result.unwrap_err(), JwtError::SubjectMismatch);
+}
+
+#[test]
+fn required_jwt_id() {
+  assert!(verify(claims(), json!({ "required_jwt_id": "token-1" })).is_ok());
+  let result = verify(claims(), json!({ "required_jwt_id": "token-2" }));
+  assert_eq!(result.unwrap_err(), JwtError::JwtIdMismatch);
+}
+
+#[test]
+fn expired_tokens_are_rejected_with_their_expiry() {
+  let claims = issued_ago(claims(), 2 * HOUR);
+  let expires_at = claims.expires_at.unwrap().as_secs();
+  let result = verify(claims.clone(), json!({}));
+  assert_eq!(
+    result.unwrap_err(),
+    JwtError::Expired { expired_at: Some(expires_at) }
+  );
+
+  // Con margen suficiente el token todavía se acepta
+  assert!(verify(claims, json!({ "leeway": "3h" })).is_ok());
+}
+
+#[test]
+fn the_default_leeway_is_fifteen_minutes() {
+  // Caducado hace diez minutos: dentro de la tolerancia por defecto
+  let claims = issued_ago(claims(), 15 * 60);
+  assert!(verify(claims.clone(), json!({})).is_ok());
+  let result = verify(claims, json!({ "leeway": 0 }));
+  assert!(matches!(result, Err(JwtError::Expired { .. })));
+
+  let claims = issued_ago(self::claims(), 25 * 60);
+  let result = verify(claims, json!({}));
+  assert!(matches!(result, Err(JwtError::Expired { .. })));
+}
+
+#[test]
+fn future_tokens_are_not_valid_yet() {
+  let mut claims = claims();
+  let not_before = claims.issued_at.unwrap() + Duration::from_secs(HOUR);
+  claims = claims.invalid_before(not_before);
+  let result = verify(claims.clone(), json!({}));
+  assert_eq!(
+    result.unwrap_err(),
+    JwtError::NotYetValid { not_before: Some(not_before.as_secs()) }
+  );
+  assert!(verify(claims, json!({ "leeway": 2 * HOUR * 1000 })).is_ok());
+}
+
+#[test]
+fn max_age_rejects_old_tokens() {
+  let mut claims = issued_ago(claims(), 10 * 60);
+  claims.expires_at = Some(Clock::now_since_epoch() + Duration::from_mins(5));
+  assert!(verify(claims.clone(), json!({ "max_age": "15m" })).is_ok());
+  let result = verify(claims, json!({ "max_age": "5m" }));
+  assert_eq!(result.unwrap_err(), JwtError::TooOld);
+}
+
+#[test]
+fn every_option_applies_at_once() {
+  let options = json!({
+    "allowed_issuers": ["https://auth.example.com"],
+    "allowed_audiences": ["api"],
+    "required_subject": "user-42",
+    "required_jwt_id": "token-1",
+    "leeway": "30s",
+    "max_age": "1h",
+  });
+  assert!(verify(claims(), options).is_ok());
+}
@@ -0,0 +1,55 @@
+use jwt_wasm::core::{self, secret_signing_key, secret_verifying_key};
+use jwt_wasm::{Algorithm, Audience, JwtOptions, VerifiedToken, VerifyOptions};
+use serde_json::json;
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+fn verified_token() -> VerifiedToken {
+  let options: JwtOptions = serde_json::from_value(json!({
+    "secret": SECRET,
+    "expires_in": "10m",
+    "kid": "hmac-1",
+    "issuer": "https://auth.example.com",
+    "subject": "user-42",
+    "audience": "api",
+    "jwt_id": "token-1",
+  }))
+  .unwrap();
+  let key = secret_signing_key(&options).unwrap();
+  let token =
+    core::sign(&json!({ "roles": ["admin"] }), &key, &options).unwrap();
+  let key =
+    secret_verifying_key(Algorithm::HS256, SECRET.into(), &Default::default())
+      .unwrap();
+  core::verify_full(&token, &key, &VerifyOptions::default()).unwrap()
+}
+
+#[test]
+fn header_claims_and_payload_are_returned() {
+  let verified = verified_token();
+  assert_eq!(verified.header.alg, "HS256");
+  assert_eq!(verified.header.typ.as_deref(), Some("JWT"));
+  assert_eq!(verified.header.kid.as_deref(), Some("hmac-1"));
+
+  let claims = &verified.claims;
+  let issued_at = claims.iat.unwrap();
+  assert_eq!(claims.exp, Some(issued_at + 600));
+  assert_eq!(claims.nbf, Some(issued_at));
+  assert_eq!(claims.iss.as_deref(), Some("https://auth.example.com"));
+  assert_eq!(claims.sub.as_deref(), Some("user-42"));
+  assert!(matches!(&claims.aud, Some(Audience::One(aud)) if aud == "api"));
+  assert_eq!(claims.jti.as_deref(), Some("token-1"));
+
+  // El payload solo lleva los claims personalizados
+  assert_eq!(verified.payload, json!({ "roles": ["admin"] }));
+}
+
+#[test]
+fn timestamps_serialize_as_numbers() {
+  let json = serde_json::to_value(verified_token()).unwrap();
+  assert!(json["claims"]["iat"].is_u64());
+  assert!(json["claims"]["exp"].is_u64());
+  assert_eq!(json["claims"]["aud"], "api");
+  assert_eq!(json["header"]["kid"], "hmac-1");
+  assert_eq!(json["payload"]["roles"][0], "admin");
+}